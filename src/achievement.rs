@@ -0,0 +1,115 @@
+use super::{
+	api::riot as riot_api, config::riot::Locale, db, db::riot as riot_db, message::riot::locale,
+};
+
+pub mod lol;
+pub mod tft;
+
+// Stored game counts worth an announcement
+const MILESTONES: [i64; 3] = [100, 500, 1000];
+
+// Reaching a tier above the previous league of the queue
+fn new_tier(tier: Option<riot_api::Tier>, history: &riot_db::History) -> Option<String> {
+	let tier = tier?;
+	let (previous_tier, ..) = history.previous_league?;
+	(u8::from(tier) > u8::from(previous_tier)).then(|| format!("tier-{tier}"))
+}
+
+fn milestone(history: &riot_db::History) -> Option<String> {
+	MILESTONES
+		.contains(&history.games)
+		.then(|| format!("games-{}", history.games))
+}
+
+// Human readable name of a stored achievement identifier, translated by the
+// `labels` of a locale, e.g. "Reached {tier}"
+pub fn describe(achievement: &str, locale: &Locale) -> String {
+	if let Some(tier) = achievement.strip_prefix("tier-") {
+		let tier = tier
+			.parse()
+			.map_or_else(|_| String::from(tier), |tier| locale::tier(locale, tier));
+		return locale::label(locale, "Reached {tier}").replace("{tier}", &tier);
+	}
+	if let Some(games) = achievement.strip_prefix("games-") {
+		return locale::label(locale, "{games} tracked games").replace("{games}", games);
+	}
+	if let Some(set) = achievement.strip_prefix("first-place-set-") {
+		return locale::label(locale, "First 1st place of set {set}").replace("{set}", set);
+	}
+	match achievement {
+		"pentakill" => locale::label(locale, "First pentakill"),
+		"perfect-kda" => locale::label(locale, "First perfect KDA game"),
+		"one-star-three-cost-win" => locale::label(locale, "1st place with 1-star 3-costs"),
+		achievement => String::from(achievement),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn rules() {
+		let history = |games, previous_league| riot_db::History {
+			games,
+			previous_league,
+		};
+		let gold = Some((riot_api::Tier::GOLD, riot_api::Rank::I, 80));
+
+		// Only above the previous league of the queue
+		assert_eq!(
+			new_tier(Some(riot_api::Tier::PLATINUM), &history(10, gold)),
+			Some(String::from("tier-PLATINUM"))
+		);
+		assert_eq!(
+			new_tier(Some(riot_api::Tier::GOLD), &history(10, gold)),
+			None
+		);
+		assert_eq!(
+			new_tier(Some(riot_api::Tier::SILVER), &history(10, gold)),
+			None
+		);
+		assert_eq!(
+			new_tier(Some(riot_api::Tier::PLATINUM), &history(10, None)),
+			None
+		);
+		assert_eq!(new_tier(None, &history(10, gold)), None);
+
+		assert_eq!(
+			milestone(&history(100, None)),
+			Some(String::from("games-100"))
+		);
+		assert_eq!(
+			milestone(&history(1000, None)),
+			Some(String::from("games-1000"))
+		);
+		assert_eq!(milestone(&history(99, None)), None);
+		assert_eq!(milestone(&history(101, None)), None);
+	}
+
+	#[test]
+	fn descriptions() {
+		let mut locale = Locale::default();
+		assert_eq!(describe("tier-GOLD", &locale), "Reached GOLD");
+		assert_eq!(
+			describe("first-place-set-9", &locale),
+			"First 1st place of set 9"
+		);
+		assert_eq!(describe("unknown", &locale), "unknown");
+
+		locale
+			.tiers
+			.insert(String::from("GOLD"), String::from("ORO"));
+		locale.labels.insert(
+			String::from("Reached {tier}"),
+			String::from("Alcanzó {tier}"),
+		);
+		locale.labels.insert(
+			String::from("First pentakill"),
+			String::from("Primer pentakill"),
+		);
+		assert_eq!(describe("tier-GOLD", &locale), "Alcanzó ORO");
+		assert_eq!(describe("pentakill", &locale), "Primer pentakill");
+		assert_eq!(describe("games-100", &locale), "100 tracked games");
+	}
+}
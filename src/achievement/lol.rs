@@ -0,0 +1,131 @@
+use super::{
+	db,
+	riot_api::lol::{Game, League, Participant, Player},
+	riot_api::Platform,
+	riot_db::History,
+};
+
+type Rule = fn(&Game, &Participant, Option<&League>, &History) -> Option<String>;
+
+const RULES: [Rule; 4] = [pentakill, perfect_kda, new_tier, milestone];
+
+fn pentakill(
+	_: &Game,
+	participant: &Participant,
+	_: Option<&League>,
+	_: &History,
+) -> Option<String> {
+	(participant.penta_kills > 0).then(|| String::from("pentakill"))
+}
+
+// No deaths, but some kills or assists
+fn perfect_kda(
+	_: &Game,
+	participant: &Participant,
+	_: Option<&League>,
+	_: &History,
+) -> Option<String> {
+	(participant.deaths == 0 && participant.kills + participant.assists > 0)
+		.then(|| String::from("perfect-kda"))
+}
+
+fn new_tier(
+	_: &Game,
+	_: &Participant,
+	league: Option<&League>,
+	history: &History,
+) -> Option<String> {
+	super::new_tier(league.and_then(|league| league.tier), history)
+}
+
+fn milestone(_: &Game, _: &Participant, _: Option<&League>, history: &History) -> Option<String> {
+	super::milestone(history)
+}
+
+// Identifiers of the achievements a game unlocks for a player
+pub fn evaluate(
+	game: &Game,
+	participant: &Participant,
+	league: Option<&League>,
+	history: &History,
+) -> Vec<String> {
+	RULES
+		.iter()
+		.filter_map(|rule| rule(game, participant, league, history))
+		.collect()
+}
+
+// Achievements unlocked for the first time by a stored game, by player
+pub async fn unlock<T>(
+	pool: &db::Pool,
+	game: &Game,
+	platform: Platform,
+	players_participants_leagues_: &[(&Player, &Participant, Option<League>, T)],
+) -> Vec<Vec<String>> {
+	let mut players_achievements = Vec::default();
+	for (player, participant, league, ..) in players_participants_leagues_ {
+		let mut achievements = Vec::default();
+		if let Ok(history) = db::riot::lol::get_history(pool, player, game).await {
+			for achievement in evaluate(game, participant, league.as_ref(), &history) {
+				if db::riot::lol::insert_achievement(pool, player, &achievement, game, platform)
+					.await
+					.unwrap_or_default()
+				{
+					achievements.push(achievement);
+				}
+			}
+		}
+		players_achievements.push(achievements);
+	}
+	players_achievements
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn game() -> Game {
+		serde_json::from_str(include_str!("../../assets/tests/lol-game.json")).unwrap()
+	}
+
+	const HISTORY: History = History {
+		games: 10,
+		previous_league: None,
+	};
+
+	#[test]
+	fn rules() {
+		let game = game();
+		let mut participant = game.info.participants[0].clone();
+		assert!(evaluate(&game, &participant, None, &HISTORY).is_empty());
+
+		participant.penta_kills = 1;
+		assert_eq!(
+			pentakill(&game, &participant, None, &HISTORY),
+			Some(String::from("pentakill"))
+		);
+
+		// No deaths, with kills or assists
+		participant.deaths = 0;
+		assert_eq!(
+			perfect_kda(&game, &participant, None, &HISTORY),
+			Some(String::from("perfect-kda"))
+		);
+		participant.kills = 0;
+		participant.assists = 0;
+		assert_eq!(perfect_kda(&game, &participant, None, &HISTORY), None);
+
+		assert_eq!(
+			evaluate(
+				&game,
+				&game.info.participants[0],
+				None,
+				&History {
+					games: 100,
+					previous_league: None,
+				}
+			),
+			[String::from("games-100")]
+		);
+	}
+}
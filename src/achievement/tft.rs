@@ -0,0 +1,138 @@
+use super::{
+	db,
+	riot_api::tft::{Game, League, Participant, Player},
+	riot_api::Platform,
+	riot_db::History,
+};
+
+// Unit rarity of the 3-cost champions
+const THREE_COST: i32 = 2;
+
+type Rule = fn(&Game, &Participant, Option<&League>, &History) -> Option<String>;
+
+const RULES: [Rule; 4] = [first_place, one_star_three_cost_win, new_tier, milestone];
+
+// First of the set
+fn first_place(
+	game: &Game,
+	participant: &Participant,
+	_: Option<&League>,
+	_: &History,
+) -> Option<String> {
+	(participant.placement == 1).then(|| format!("first-place-set-{}", game.info.tft_set_number))
+}
+
+// 1st place without upgrading any of the 3-cost units
+fn one_star_three_cost_win(
+	_: &Game,
+	participant: &Participant,
+	_: Option<&League>,
+	_: &History,
+) -> Option<String> {
+	let three_costs = participant
+		.units
+		.iter()
+		.filter(|unit| unit.rarity == THREE_COST)
+		.collect::<Vec<_>>();
+	(participant.placement == 1
+		&& !three_costs.is_empty()
+		&& three_costs.iter().all(|unit| unit.tier == 1))
+	.then(|| String::from("one-star-three-cost-win"))
+}
+
+fn new_tier(
+	_: &Game,
+	_: &Participant,
+	league: Option<&League>,
+	history: &History,
+) -> Option<String> {
+	super::new_tier(league.and_then(|league| league.tier), history)
+}
+
+fn milestone(_: &Game, _: &Participant, _: Option<&League>, history: &History) -> Option<String> {
+	super::milestone(history)
+}
+
+// Identifiers of the achievements a game unlocks for a player
+pub fn evaluate(
+	game: &Game,
+	participant: &Participant,
+	league: Option<&League>,
+	history: &History,
+) -> Vec<String> {
+	RULES
+		.iter()
+		.filter_map(|rule| rule(game, participant, league, history))
+		.collect()
+}
+
+// Achievements unlocked for the first time by a stored game, by player
+pub async fn unlock<T>(
+	pool: &db::Pool,
+	game: &Game,
+	platform: Platform,
+	players_participants_leagues_: &[(&Player, &Participant, Option<League>, T)],
+) -> Vec<Vec<String>> {
+	let mut players_achievements = Vec::default();
+	for (player, participant, league, ..) in players_participants_leagues_ {
+		let mut achievements = Vec::default();
+		if let Ok(history) = db::riot::tft::get_history(pool, player, game).await {
+			for achievement in evaluate(game, participant, league.as_ref(), &history) {
+				if db::riot::tft::insert_achievement(pool, player, &achievement, game, platform)
+					.await
+					.unwrap_or_default()
+				{
+					achievements.push(achievement);
+				}
+			}
+		}
+		players_achievements.push(achievements);
+	}
+	players_achievements
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn game() -> Game {
+		serde_json::from_str(include_str!("../../assets/tests/tft-game.json")).unwrap()
+	}
+
+	const HISTORY: History = History {
+		games: 10,
+		previous_league: None,
+	};
+
+	#[test]
+	fn rules() {
+		let game = game();
+		let (first, fifth) = (&game.info.participants[0], &game.info.participants[1]);
+		assert_eq!(
+			evaluate(&game, first, None, &HISTORY),
+			[
+				String::from("first-place-set-9"),
+				String::from("one-star-three-cost-win")
+			]
+		);
+		assert!(evaluate(&game, fifth, None, &HISTORY).is_empty());
+
+		// Every 3-cost unit at 1 star
+		let mut participant = first.clone();
+		participant.units[1].rarity = THREE_COST;
+		assert_eq!(
+			one_star_three_cost_win(&game, &participant, None, &HISTORY),
+			None
+		);
+		participant.units[1].tier = 1;
+		assert_eq!(
+			one_star_three_cost_win(&game, &participant, None, &HISTORY),
+			Some(String::from("one-star-three-cost-win"))
+		);
+		participant.units.clear();
+		assert_eq!(
+			one_star_three_cost_win(&game, &participant, None, &HISTORY),
+			None
+		);
+	}
+}
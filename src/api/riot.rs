@@ -1,13 +1,48 @@
+pub mod budget;
 pub mod lol;
 pub mod tft;
 
 const LAST_COUNT: i32 = 100;
 
+pub use budget::{Budget, Endpoint};
 pub use riven::{
-	consts::{Division as Rank, GameMode, PlatformRoute as Platform, Queue, QueueType, Tier},
-	Result, RiotApi as Api,
+	consts::{Division as Rank, GameMode, PlatformRoute as Platform, Queue, QueueType, Team, Tier},
+	Result,
 };
 
+pub struct Api {
+	riot: riven::RiotApi,
+	budget: Budget,
+}
+
+impl Api {
+	pub fn new(key: String, budget: Budget) -> Self {
+		Self {
+			riot: riven::RiotApi::new(key),
+			budget,
+		}
+	}
+
+	pub fn budget(&self) -> &Budget {
+		&self.budget
+	}
+}
+
+// Count the response of a request in the metrics, by HTTP status of errors.
+// The error type belongs to riven.
+#[allow(clippy::result_large_err)]
+fn observe<T>(product: &'static str, endpoint: Endpoint, result: Result<T>) -> Result<T> {
+	let status = match &result {
+		Ok(_) => String::from("ok"),
+		Err(err) => err.status_code().map_or_else(
+			|| String::from("error"),
+			|status| status.as_u16().to_string(),
+		),
+	};
+	crate::metrics::riot_request(product, endpoint.as_str(), &status);
+	result
+}
+
 pub fn are_same_queue(queue_type: &QueueType, queue: Queue) -> bool {
 	match (queue_type, queue) {
 		// LOL
@@ -0,0 +1,356 @@
+use std::{collections::VecDeque, str::FromStr, sync::Mutex};
+
+use tokio::{
+	sync::Notify,
+	time::{Duration, Instant},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Endpoint {
+	Player,
+	GameIds,
+	Game,
+	Leagues,
+	Spectator,
+}
+
+impl Endpoint {
+	pub const ALL: [Endpoint; 5] = [
+		Endpoint::Player,
+		Endpoint::GameIds,
+		Endpoint::Game,
+		Endpoint::Leagues,
+		Endpoint::Spectator,
+	];
+
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Endpoint::Player => "player",
+			Endpoint::GameIds => "game-ids",
+			Endpoint::Game => "game",
+			Endpoint::Leagues => "leagues",
+			Endpoint::Spectator => "spectator",
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Window {
+	pub limit: usize,
+	pub duration: Duration,
+}
+
+impl FromStr for Window {
+	type Err = String;
+
+	// "20/1s", "100/120s", "1000/10m", or as in Riot's rate limit headers,
+	// "20:1" and "100:120" (seconds)
+	fn from_str(window: &str) -> Result<Self, Self::Err> {
+		let error = || format!("Invalid rate limit window \"{window}\" (expected e.g. \"20/1s\")");
+		let (limit, duration) = match window.trim().split_once(':') {
+			Some((limit, seconds)) => (limit, format!("{}s", seconds.trim())),
+			None => {
+				let (limit, duration) = window.trim().split_once('/').ok_or_else(error)?;
+				(limit, String::from(duration.trim()))
+			}
+		};
+		let limit = limit.trim().parse().map_err(|_| error())?;
+		let (value, unit) = duration.split_at(duration.len().saturating_sub(1));
+		let value: u64 = value.parse().map_err(|_| error())?;
+		let duration = match unit {
+			"s" => Duration::from_secs(value),
+			"m" => Duration::from_secs(value.saturating_mul(60)),
+			"h" => Duration::from_secs(value.saturating_mul(3600)),
+			_ => return Err(error()),
+		};
+		if limit == 0 || duration.is_zero() {
+			return Err(error());
+		}
+		Ok(Self { limit, duration })
+	}
+}
+
+#[derive(Default)]
+struct State {
+	history: VecDeque<Instant>,
+	waiting: [usize; Endpoint::ALL.len()],
+	served: [u64; Endpoint::ALL.len()],
+	sent: [u64; Endpoint::ALL.len()],
+	peak: Vec<usize>,
+}
+
+pub struct Budget {
+	windows: Vec<Window>,
+	state: Mutex<State>,
+	notify: Notify,
+}
+
+impl FromStr for Budget {
+	type Err = String;
+
+	// "20/1s,100/120s" or "20:1,100:120"
+	fn from_str(windows: &str) -> Result<Self, Self::Err> {
+		let windows = windows
+			.split(',')
+			.map(Window::from_str)
+			.collect::<Result<Vec<_>, _>>()?;
+		let state = State {
+			peak: vec![0; windows.len()],
+			..State::default()
+		};
+		Ok(Self {
+			windows,
+			state: Mutex::new(state),
+			notify: Notify::new(),
+		})
+	}
+}
+
+impl Budget {
+	// Wait for a free slot in every window. Endpoints waiting at the same time
+	// are served in turns so that no request kind starves the others.
+	pub async fn acquire(&self, endpoint: Endpoint) {
+		let index = endpoint as usize;
+		{
+			let mut state = self.state.lock().unwrap();
+			if state.waiting[index] == 0 {
+				let floor = Self::waiting_served(&state).min().unwrap_or_default();
+				state.served[index] = state.served[index].max(floor);
+			}
+			state.waiting[index] += 1;
+		}
+		let _waiting = Waiting(self, index);
+
+		loop {
+			let notified = self.notify.notified();
+			let delay = {
+				let mut state = self.state.lock().unwrap();
+				let now = Instant::now();
+				self.prune(&mut state, now);
+				if Self::waiting_served(&state).any(|served| served < state.served[index]) {
+					None
+				} else {
+					match self.delay(&state, now) {
+						Some(delay) => Some(delay),
+						None => {
+							state.history.push_back(now);
+							state.served[index] += 1;
+							state.sent[index] += 1;
+							state.peak = self
+								.windows
+								.iter()
+								.zip(&state.peak)
+								.map(|(window, peak)| (*peak).max(Self::used(&state, window, now)))
+								.collect();
+							return;
+						}
+					}
+				}
+			};
+
+			match delay {
+				Some(delay) => tokio::time::sleep(delay).await,
+				None => notified.await,
+			}
+		}
+	}
+
+	// Log requests sent per endpoint and the highest usage of every window
+	// since the last report
+	pub fn report(&self, key: &str) {
+		let mut state = self.state.lock().unwrap();
+		let now = Instant::now();
+		self.prune(&mut state, now);
+		for (window, peak) in self.windows.iter().zip(&state.peak) {
+			let used = Self::used(&state, window, now);
+			tracing::info!(
+				key,
+				window = format!("{}/{}s", window.limit, window.duration.as_secs()),
+				used,
+				peak,
+				usage = format!("{:.0}%", 100.0 * *peak as f64 / window.limit as f64),
+				"Riot API rate budget usage"
+			);
+		}
+		for endpoint in Endpoint::ALL {
+			tracing::info!(
+				key,
+				endpoint = endpoint.as_str(),
+				sent = state.sent[endpoint as usize],
+				waiting = state.waiting[endpoint as usize],
+				"Riot API requests"
+			);
+		}
+		state.peak.iter_mut().for_each(|peak| *peak = 0);
+	}
+
+	fn waiting_served(state: &State) -> impl Iterator<Item = u64> + '_ {
+		Endpoint::ALL
+			.iter()
+			.map(|endpoint| *endpoint as usize)
+			.filter(|index| state.waiting[*index] > 0)
+			.map(|index| state.served[index])
+	}
+
+	fn prune(&self, state: &mut State, now: Instant) {
+		let longest = self
+			.windows
+			.iter()
+			.map(|window| window.duration)
+			.max()
+			.unwrap_or_default();
+		while state
+			.history
+			.front()
+			.is_some_and(|sent| now.duration_since(*sent) >= longest)
+		{
+			state.history.pop_front();
+		}
+	}
+
+	fn used(state: &State, window: &Window, now: Instant) -> usize {
+		state
+			.history
+			.iter()
+			.rev()
+			.take_while(|sent| now.duration_since(**sent) < window.duration)
+			.count()
+	}
+
+	fn delay(&self, state: &State, now: Instant) -> Option<Duration> {
+		self.windows
+			.iter()
+			.filter(|window| Self::used(state, window, now) >= window.limit)
+			.map(|window| {
+				let oldest = state.history[state.history.len() - window.limit];
+				(oldest + window.duration).saturating_duration_since(now)
+			})
+			.max()
+	}
+}
+
+// Request waiting for the budget, until sent or cancelled (e.g. by a task
+// stopped at shutdown), after which the others may take its turn
+struct Waiting<'a>(&'a Budget, usize);
+
+impl Drop for Waiting<'_> {
+	fn drop(&mut self) {
+		let Waiting(budget, index) = self;
+		budget.state.lock().unwrap().waiting[*index] -= 1;
+		budget.notify.notify_waiters();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn windows(budget: &Budget) -> Vec<(usize, u64)> {
+		budget
+			.windows
+			.iter()
+			.map(|window| (window.limit, window.duration.as_secs()))
+			.collect()
+	}
+
+	#[test]
+	fn parse() {
+		for windows_ in ["20/1s,100/120s", "20/1s, 100/2m", "20:1,100:120"] {
+			assert_eq!(
+				windows(&windows_.parse().unwrap()),
+				[(20, 1), (100, 120)],
+				"{windows_}"
+			);
+		}
+		assert_eq!(windows(&"500/1h".parse().unwrap()), [(500, 3600)]);
+		for windows_ in [
+			"",
+			"20",
+			"20/",
+			"/1s",
+			"20/1",
+			"20/1d",
+			"20/s",
+			"0/1s",
+			"20/0s",
+			"-1/1s",
+			"20:",
+			"20:1s",
+			"a:1",
+			"20:0",
+			"20:1,",
+			"20/1s;100/120s",
+		] {
+			assert!(windows_.parse::<Budget>().is_err(), "{windows_}");
+		}
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn expiry() {
+		let budget = "2/1s,3/10s".parse::<Budget>().unwrap();
+		let start = Instant::now();
+		let mut sent = Vec::default();
+		for _ in 0..5 {
+			budget.acquire(Endpoint::Game).await;
+			sent.push(start.elapsed().as_millis());
+			tokio::time::sleep(Duration::from_millis(300)).await;
+		}
+		// Two requests in the first second, the third one when the first
+		// expires, then none until it leaves the 10 seconds window
+		assert_eq!(sent, [0, 300, 1000, 10000, 10300]);
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn fair_sharing() {
+		let budget = "1/1s".parse::<Budget>().unwrap();
+		let sent = Mutex::new(Vec::default());
+		let acquire = |endpoint| {
+			let (budget, sent) = (&budget, &sent);
+			async move {
+				budget.acquire(endpoint).await;
+				sent.lock().unwrap().push(endpoint);
+			}
+		};
+		// Game requests queued first do not delay the others until they are
+		// all sent
+		tokio::join!(
+			acquire(Endpoint::Game),
+			acquire(Endpoint::Game),
+			acquire(Endpoint::Game),
+			acquire(Endpoint::Game),
+			acquire(Endpoint::Player),
+			acquire(Endpoint::Leagues),
+		);
+		assert_eq!(
+			sent.into_inner().unwrap(),
+			[
+				Endpoint::Game,
+				Endpoint::Game,
+				Endpoint::Player,
+				Endpoint::Leagues,
+				Endpoint::Game,
+				Endpoint::Game
+			]
+		);
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn cancelled() {
+		let budget = "1/1s".parse::<Budget>().unwrap();
+		budget.acquire(Endpoint::Player).await;
+		// Game requests have not been served yet, so a waiting one goes first
+		assert!(
+			tokio::time::timeout(Duration::from_millis(500), budget.acquire(Endpoint::Game))
+				.await
+				.is_err()
+		);
+		assert_eq!(
+			budget.state.lock().unwrap().waiting,
+			[0; Endpoint::ALL.len()]
+		);
+		// Until it is cancelled
+		let start = Instant::now();
+		budget.acquire(Endpoint::Player).await;
+		assert_eq!(start.elapsed().as_millis(), 500);
+	}
+}
@@ -1,4 +1,4 @@
-use super::{Api, Platform, Result, LAST_COUNT};
+use super::{Api, Endpoint, Platform, Result, LAST_COUNT};
 
 pub use riven::models::{
 	league_v4::LeagueEntry as League,
@@ -7,144 +7,206 @@ pub use riven::models::{
 };
 
 pub async fn get_player(api: &Api, platform: Platform, name: &str) -> Result<Option<Player>> {
-	api.summoner_v4()
-		.get_by_summoner_name(platform, name)
-		.await
-		.map_err(|err| {
-			// TODO: inspect_err // result_option_inspect #91345 // https://github.com/rust-lang/rust/issues/91345
-			tracing::error!(
-				platform = platform.as_region_str(),
-				player = name,
-				error = err.source_reqwest_error().to_string(),
-				response = err.status_code().map(|err| err.to_string()),
-				"Error getting Riot LOL player"
-			);
-			err
-		})
-		.map(|player| {
-			// TODO: inspect // result_option_inspect #91345 // https://github.com/rust-lang/rust/issues/91345
-			player
-				.or_else(|| {
-					tracing::error!(
-						platform = platform.as_region_str(),
-						player = name,
-						"Error getting Riot LOL player"
-					);
-					None
-				})
-				.map(|player| {
-					// TODO: inspect // result_option_inspect #91345 // https://github.com/rust-lang/rust/issues/91345
-					tracing::debug!(
-						platform = platform.as_region_str(),
-						player = player.name,
-						puuid = player.puuid,
-						"Success getting Riot LOL player"
-					);
-					player
-				})
-		})
+	api.budget.acquire(Endpoint::Player).await;
+	super::observe(
+		"lol",
+		Endpoint::Player,
+		api.riot
+			.summoner_v4()
+			.get_by_summoner_name(platform, name)
+			.await,
+	)
+	.inspect_err(|err| {
+		tracing::error!(
+			platform = platform.as_region_str(),
+			player = name,
+			error = err.source_reqwest_error().to_string(),
+			response = err.status_code().map(|err| err.to_string()),
+			"Error getting Riot LOL player"
+		);
+	})
+	.map(|player| {
+		// TODO: inspect // result_option_inspect #91345 // https://github.com/rust-lang/rust/issues/91345
+		player
+			.or_else(|| {
+				tracing::error!(
+					platform = platform.as_region_str(),
+					player = name,
+					"Error getting Riot LOL player"
+				);
+				None
+			})
+			.inspect(|player| {
+				tracing::debug!(
+					platform = platform.as_region_str(),
+					player = player.name,
+					puuid = player.puuid,
+					"Success getting Riot LOL player"
+				);
+			})
+	})
 }
 
-pub async fn get_last_game_ids(
+// Every game identifier between `start_time` and `end_time` (milliseconds), newest first
+pub async fn get_game_ids(
 	api: &Api,
 	platform: Platform,
 	player: &Player,
+	start_time: i64,
+	end_time: Option<i64>,
 ) -> Result<Vec<String>> {
-	api.match_v5()
-		.get_match_ids_by_puuid(
-			platform.to_regional(),
-			&player.puuid,
-			Some(LAST_COUNT),
-			None,
-			None,
-			None,
-			None,
-			None,
+	let mut game_ids = Vec::default();
+	loop {
+		let page = get_game_ids_page(
+			api,
+			platform,
+			player,
+			start_time / 1000,
+			end_time.map(|end_time| end_time / 1000),
+			game_ids.len(),
 		)
-		.await
-		.map_err(|err| {
-			// TODO: inspect_err // result_option_inspect #91345 // https://github.com/rust-lang/rust/issues/91345
-			tracing::error!(
-				platform = platform.as_region_str(),
-				player = player.name,
-				error = err.source_reqwest_error().to_string(),
-				response = err.status_code().map(|err| err.to_string()),
-				"Error getting Riot LOL game identifiers"
-			);
-			err
-		})
-		.map(|game_ids| {
-			// TODO: inspect // result_option_inspect #91345 // https://github.com/rust-lang/rust/issues/91345
-			tracing::trace!(
-				platform = platform.as_region_str(),
-				player = player.name,
-				n = game_ids.len(),
-				"Success downloading Riot LOL game identifiers"
-			);
-			game_ids
-		})
+		.await?;
+		let n = page.len();
+		game_ids.extend(page);
+		if n < LAST_COUNT as usize {
+			break Ok(game_ids);
+		}
+	}
+}
+
+async fn get_game_ids_page(
+	api: &Api,
+	platform: Platform,
+	player: &Player,
+	start_time: i64,
+	end_time: Option<i64>,
+	start: usize,
+) -> Result<Vec<String>> {
+	let start = i32::try_from(start).unwrap_or(i32::MAX);
+	api.budget.acquire(Endpoint::GameIds).await;
+	super::observe(
+		"lol",
+		Endpoint::GameIds,
+		api.riot
+			.match_v5()
+			.get_match_ids_by_puuid(
+				platform.to_regional(),
+				&player.puuid,
+				Some(LAST_COUNT),
+				end_time,
+				None,
+				Some(start_time),
+				Some(start),
+				None,
+			)
+			.await,
+	)
+	.inspect_err(|err| {
+		tracing::error!(
+			platform = platform.as_region_str(),
+			player = player.name,
+			error = err.source_reqwest_error().to_string(),
+			response = err.status_code().map(|err| err.to_string()),
+			"Error getting Riot LOL game identifiers"
+		);
+	})
+	.inspect(|game_ids| {
+		tracing::trace!(
+			platform = platform.as_region_str(),
+			player = player.name,
+			start,
+			n = game_ids.len(),
+			"Success downloading Riot LOL game identifiers"
+		);
+	})
 }
 
 pub async fn get_game(api: &Api, platform: Platform, game_id: &str) -> Result<Option<Game>> {
-	api.match_v5()
-		.get_match(platform.to_regional(), game_id)
-		.await
-		.map_err(|err| {
-			// TODO: inspect_err // result_option_inspect #91345 // https://github.com/rust-lang/rust/issues/91345
-			tracing::error!(
-				platform = platform.as_region_str(),
-				game = game_id,
-				error = err.source_reqwest_error().to_string(),
-				response = err.status_code().map(|err| err.to_string()),
-				"Error getting Riot LOL game"
-			);
-			err
-		})
-		.map(|game| {
-			// TODO: inspect // result_option_inspect #91345 // https://github.com/rust-lang/rust/issues/91345
-			game.map(|game| {
-				// TODO: inspect // result_option_inspect #91345 // https://github.com/rust-lang/rust/issues/91345
+	api.budget.acquire(Endpoint::Game).await;
+	super::observe(
+		"lol",
+		Endpoint::Game,
+		api.riot
+			.match_v5()
+			.get_match(platform.to_regional(), game_id)
+			.await,
+	)
+	.inspect_err(|err| {
+		tracing::error!(
+			platform = platform.as_region_str(),
+			game = game_id,
+			error = err.source_reqwest_error().to_string(),
+			response = err.status_code().map(|err| err.to_string()),
+			"Error getting Riot LOL game"
+		);
+	})
+	.map(|game| {
+		// TODO: inspect // result_option_inspect #91345 // https://github.com/rust-lang/rust/issues/91345
+		game.inspect(|game| {
+			let datetime_to_string = |datetime: chrono::naive::NaiveDateTime| {
+				datetime.format("%y-%m-%dT%H:%M:%S").to_string()
+			};
 
-				let datetime_to_string = |datetime: chrono::naive::NaiveDateTime| {
-					datetime.format("%y-%m-%dT%H:%M:%S").to_string()
-				};
+			let start =
+				chrono::naive::NaiveDateTime::from_timestamp_millis(game.info.game_start_timestamp)
+					.map(datetime_to_string);
 
-				let start = chrono::naive::NaiveDateTime::from_timestamp_millis(
-					game.info.game_start_timestamp,
-				)
+			let end = game
+				.info
+				.game_end_timestamp
+				.and_then(chrono::naive::NaiveDateTime::from_timestamp_millis)
 				.map(datetime_to_string);
 
-				let end = game
-					.info
-					.game_end_timestamp
-					.and_then(chrono::naive::NaiveDateTime::from_timestamp_millis)
-					.map(datetime_to_string);
-
-				tracing::debug!(
-					platform = platform.as_region_str(),
-					game = game.metadata.match_id,
-					start,
-					end,
-					"Success getting Riot LOL game"
-				);
-				game
-			})
+			tracing::debug!(
+				platform = platform.as_region_str(),
+				game = game.metadata.match_id,
+				start,
+				end,
+				"Success getting Riot LOL game"
+			);
 		})
+	})
 }
 
 pub async fn get_leagues(api: &Api, platform: Platform, player: &Player) -> Result<Vec<League>> {
-	api.league_v4()
-		.get_league_entries_for_summoner(platform, &player.id)
-		.await
-		.map_err(|err| {
-			// TODO: inspect_err // result_option_inspect #91345 // https://github.com/rust-lang/rust/issues/91345
-			tracing::error!(
-				platform = platform.as_region_str(),
-				player = player.name,
-				error = err.source_reqwest_error().to_string(),
-				response = err.status_code().map(|err| err.to_string()),
-				"Error getting Riot LOL leagues"
-			);
-			err
-		})
+	api.budget.acquire(Endpoint::Leagues).await;
+	super::observe(
+		"lol",
+		Endpoint::Leagues,
+		api.riot
+			.league_v4()
+			.get_league_entries_for_summoner(platform, &player.id)
+			.await,
+	)
+	.inspect_err(|err| {
+		tracing::error!(
+			platform = platform.as_region_str(),
+			player = player.name,
+			error = err.source_reqwest_error().to_string(),
+			response = err.status_code().map(|err| err.to_string()),
+			"Error getting Riot LOL leagues"
+		);
+	})
+}
+
+pub async fn is_playing(api: &Api, platform: Platform, player: &Player) -> Result<bool> {
+	api.budget.acquire(Endpoint::Spectator).await;
+	super::observe(
+		"lol",
+		Endpoint::Spectator,
+		api.riot
+			.spectator_v4()
+			.get_current_game_info_by_summoner(platform, &player.id)
+			.await,
+	)
+	.inspect_err(|err| {
+		tracing::error!(
+			platform = platform.as_region_str(),
+			player = player.name,
+			error = err.source_reqwest_error().to_string(),
+			response = err.status_code().map(|err| err.to_string()),
+			"Error getting Riot LOL spectator status"
+		);
+	})
+	.map(|game| game.is_some())
 }
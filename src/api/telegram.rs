@@ -1,13 +1,13 @@
 pub use teloxide::{
 	adaptors::throttle::{Limits, Throttle},
 	types::ChatId,
-	Bot as Api,
+	Bot as Api, RequestError,
 };
 
 use teloxide::{
-	payloads::SendMessageSetters,
+	payloads::{GetUpdatesSetters, SendMessageSetters, SendPhotoSetters},
 	requests::{Request, Requester},
-	types::ParseMode,
+	types::{InputFile, ParseMode, UpdateKind},
 };
 
 pub async fn send_message<E: std::fmt::Display>(
@@ -20,9 +20,57 @@ pub async fn send_message<E: std::fmt::Display>(
 		.disable_notification(true)
 		.send()
 		.await
-		.map_err(|err| {
+		.inspect_err(|err| {
 			tracing::error!(error = err.to_string(), "Error sending Telegram message");
-			err
 		})
 		.map(|_| ())
 }
+
+pub async fn send_photo<E: std::fmt::Display>(
+	api: impl Requester<Err = E>,
+	chat: ChatId,
+	photo: &[u8],
+	caption: &str,
+) -> Result<(), E> {
+	api.send_photo(
+		chat,
+		InputFile::memory(photo.to_vec()).file_name("photo.png"),
+	)
+	.caption(caption)
+	.parse_mode(ParseMode::Html)
+	.disable_notification(true)
+	.send()
+	.await
+	.inspect_err(|err| {
+		tracing::error!(error = err.to_string(), "Error sending Telegram photo");
+	})
+	.map(|_| ())
+}
+
+// Text messages and channel posts received from `offset` on, with the offset of
+// the next call
+pub async fn get_messages<E: std::fmt::Display>(
+	api: impl Requester<Err = E>,
+	offset: i32,
+) -> Result<(Vec<(ChatId, String)>, i32), E> {
+	let updates = api
+		.get_updates()
+		.offset(offset)
+		.timeout(30)
+		.send()
+		.await
+		.inspect_err(|err| {
+			tracing::error!(error = err.to_string(), "Error getting Telegram updates");
+		})?;
+	let offset = updates.last().map_or(offset, |update| update.id + 1);
+	let messages = updates
+		.into_iter()
+		.filter_map(|update| match update.kind {
+			UpdateKind::Message(message) | UpdateKind::ChannelPost(message) => message
+				.text()
+				.map(|text| (message.chat.id, String::from(text))),
+			_ => None,
+		})
+		.collect();
+	Ok((messages, offset))
+}
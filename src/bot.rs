@@ -0,0 +1,291 @@
+use chrono::Datelike;
+
+use super::{api, config, db, message, notifier};
+
+type Players<P> = [((P, api::riot::Platform), Vec<notifier::Chat>)];
+
+pub enum Product {
+	Lol,
+	Tft,
+}
+
+impl Product {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Product::Lol => "LOL",
+			Product::Tft => "TFT",
+		}
+	}
+}
+
+pub enum Period {
+	Week,
+	Month,
+	Season,
+}
+
+impl Period {
+	// Milliseconds, seasons start on January 1st
+	pub fn start_time(&self, now: chrono::DateTime<chrono::Utc>) -> i64 {
+		match self {
+			Period::Week => (now - chrono::Duration::days(7)).timestamp_millis(),
+			Period::Month => (now - chrono::Duration::days(30)).timestamp_millis(),
+			Period::Season => now
+				.date_naive()
+				.with_ordinal(1)
+				.and_then(|date| date.and_hms_opt(0, 0, 0))
+				.map_or(0, |datetime| datetime.timestamp_millis()),
+		}
+	}
+
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Period::Week => "last 7 days",
+			Period::Month => "last 30 days",
+			Period::Season => "this season",
+		}
+	}
+}
+
+// Commands sent to the bot from a tracking chat
+pub enum Command {
+	Stats {
+		name: String,
+		product: Option<Product>,
+		period: Period,
+	},
+	Graph {
+		names: Vec<String>,
+		product: Option<Product>,
+		period: Period,
+	},
+	Achievements {
+		name: String,
+		product: Option<Product>,
+	},
+}
+
+const STATS_USAGE: &str = "Usage: /stats Name [lol|tft] [7d|30d|season]";
+const GRAPH_USAGE: &str = "Usage: /graph Name[, Name...] [lol|tft] [7d|30d|season]";
+const ACHIEVEMENTS_USAGE: &str = "Usage: /achievements Name [lol|tft]";
+
+// Parse "/command arguments" or "/command@bot arguments", other messages are
+// not for the bot. Errors are usage messages.
+pub fn parse(text: &str) -> Option<Result<Command, &'static str>> {
+	let mut words = text.split_whitespace();
+	let command = words.next()?.strip_prefix('/')?;
+	let command = command.split('@').next()?;
+	let (arguments, product, period) = parse_options(words.collect());
+	match command {
+		"stats" if arguments.is_empty() => Some(Err(STATS_USAGE)),
+		"stats" => Some(Ok(Command::Stats {
+			name: arguments.join(" "),
+			product,
+			period,
+		})),
+		"graph" if arguments.is_empty() => Some(Err(GRAPH_USAGE)),
+		"graph" => Some(Ok(Command::Graph {
+			names: arguments
+				.join(" ")
+				.split(',')
+				.map(str::trim)
+				.filter(|name| !name.is_empty())
+				.map(String::from)
+				.collect(),
+			product,
+			period,
+		})),
+		"achievements" if arguments.is_empty() => Some(Err(ACHIEVEMENTS_USAGE)),
+		"achievements" => Some(Ok(Command::Achievements {
+			name: arguments.join(" "),
+			product,
+		})),
+		_ => None,
+	}
+}
+
+// Options follow the names, which may contain spaces
+fn parse_options(mut arguments: Vec<&str>) -> (Vec<&str>, Option<Product>, Period) {
+	let mut product = None;
+	let mut period = Period::Month;
+	while let Some(argument) = arguments.last() {
+		match argument.to_lowercase().as_str() {
+			"lol" => product = Some(Product::Lol),
+			"tft" => product = Some(Product::Tft),
+			"7d" => period = Period::Week,
+			"30d" => period = Period::Month,
+			"season" => period = Period::Season,
+			_ => break,
+		}
+		arguments.pop();
+	}
+	(arguments, product, period)
+}
+
+pub async fn answer(
+	command: Command,
+	chat: &notifier::Chat,
+	db_pool: &db::Pool,
+	lol_players_platforms_chats: &Players<api::riot::lol::Player>,
+	tft_players_platforms_chats: &Players<api::riot::tft::Player>,
+	// Of the template set of the chat
+	locale: &config::riot::Locale,
+) -> message::Message {
+	match command {
+		Command::Stats {
+			name,
+			product,
+			period,
+		} => {
+			let start_time = period.start_time(chrono::Utc::now());
+			let lol_player = find(lol_players_platforms_chats, chat, &name);
+			let tft_player = find(tft_players_platforms_chats, chat, &name);
+			message::Message::html(&match (product, lol_player, tft_player) {
+				(None | Some(Product::Lol), Some(player), _) => {
+					db::riot::lol::get_results(db_pool, player, start_time)
+						.await
+						.map(|results| {
+							message::riot::stats::generate_lol(
+								&player.name,
+								period.as_str(),
+								&results,
+							)
+						})
+						.unwrap_or_else(|_| String::from("Error reading stats"))
+				}
+				(None | Some(Product::Tft), _, Some(player)) => {
+					db::riot::tft::get_results(db_pool, player, start_time)
+						.await
+						.map(|results| {
+							message::riot::stats::generate_tft(
+								&player.name,
+								period.as_str(),
+								&results,
+							)
+						})
+						.unwrap_or_else(|_| String::from("Error reading stats"))
+				}
+				_ => not_tracked(&name),
+			})
+		}
+		Command::Graph {
+			names,
+			product,
+			period,
+		} => {
+			let start_time = period.start_time(chrono::Utc::now());
+			// Without a product, LOL unless the first player is only tracked in TFT
+			let product = product.unwrap_or_else(|| {
+				match (
+					find(lol_players_platforms_chats, chat, &names[0]),
+					find(tft_players_platforms_chats, chat, &names[0]),
+				) {
+					(None, Some(_)) => Product::Tft,
+					_ => Product::Lol,
+				}
+			});
+
+			let mut names_results = Vec::default();
+			for name in &names {
+				let player_results = match product {
+					Product::Lol => match find(lol_players_platforms_chats, chat, name) {
+						Some(player) => Some((
+							player.name.as_str(),
+							db::riot::lol::get_results(db_pool, player, start_time).await,
+						)),
+						None => None,
+					},
+					Product::Tft => match find(tft_players_platforms_chats, chat, name) {
+						Some(player) => Some((
+							player.name.as_str(),
+							db::riot::tft::get_results(db_pool, player, start_time).await,
+						)),
+						None => None,
+					},
+				};
+				match player_results {
+					Some((name, Ok(results))) => names_results.push((name, results)),
+					Some((_, Err(_))) => return message::Message::html("Error reading stats"),
+					None => return message::Message::html(&not_tracked(name)),
+				}
+			}
+
+			let title = format!("{} · {}", product.as_str(), period.as_str());
+			match message::riot::graph::generate_league_points_graph(&title, &names_results) {
+				Ok(graph) => message::Message::photo(
+					graph,
+					&format!("📈 <b>{}</b> · {title}", message::escape(&names.join(", "))),
+				),
+				Err(err) => message::Message::html(&err),
+			}
+		}
+		Command::Achievements { name, product } => {
+			let lol_player = find(lol_players_platforms_chats, chat, &name);
+			let tft_player = find(tft_players_platforms_chats, chat, &name);
+			message::Message::html(&match (product, lol_player, tft_player) {
+				(None | Some(Product::Lol), Some(player), _) => {
+					db::riot::lol::get_achievements(db_pool, player)
+						.await
+						.map(|achievements| {
+							message::riot::achievements::generate(
+								&player.name,
+								Product::Lol.as_str(),
+								&achievements,
+								locale,
+							)
+						})
+						.unwrap_or_else(|_| String::from("Error reading achievements"))
+				}
+				(None | Some(Product::Tft), _, Some(player)) => {
+					db::riot::tft::get_achievements(db_pool, player)
+						.await
+						.map(|achievements| {
+							message::riot::achievements::generate(
+								&player.name,
+								Product::Tft.as_str(),
+								&achievements,
+								locale,
+							)
+						})
+						.unwrap_or_else(|_| String::from("Error reading achievements"))
+				}
+				_ => not_tracked(&name),
+			})
+		}
+	}
+}
+
+// Player tracked in a chat by name, Riot names are case and space insensitive
+fn find<'p, P: Named>(
+	players_platforms_chats: &'p Players<P>,
+	chat: &notifier::Chat,
+	name: &str,
+) -> Option<&'p P> {
+	let normalize = |name: &str| name.replace(' ', "").to_lowercase();
+	players_platforms_chats
+		.iter()
+		.find(|((player, ..), chats)| {
+			chats.contains(chat) && normalize(player.name()) == normalize(name)
+		})
+		.map(|((player, ..), ..)| player)
+}
+
+fn not_tracked(name: &str) -> String {
+	format!("{} is not tracked in this chat", message::escape(name))
+}
+
+trait Named {
+	fn name(&self) -> &str;
+}
+
+impl Named for api::riot::lol::Player {
+	fn name(&self) -> &str {
+		&self.name
+	}
+}
+
+impl Named for api::riot::tft::Player {
+	fn name(&self) -> &str {
+		&self.name
+	}
+}
@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+	/// Configuration file
+	#[arg(long, global = true, default_value = "config.json")]
+	pub config: PathBuf,
+	/// Database URL or SQLite file, overrides `database` in the configuration
+	#[arg(long, global = true)]
+	pub db: Option<String>,
+	#[command(subcommand)]
+	pub command: Option<Command>,
+}
+
+#[derive(Debug, Default, Subcommand)]
+pub enum Command {
+	/// Track players and send notifications (default)
+	#[default]
+	Run,
+	/// Validate the configuration, resolve every player and render every template
+	CheckConfig,
+	/// Store the games played between two dates (YYYY-MM-DD, inclusive) without notifying
+	Backfill {
+		from: chrono::naive::NaiveDate,
+		to: chrono::naive::NaiveDate,
+	},
+	/// Print the messages of a stored game rendered with the current templates
+	Rerender {
+		game_id: String,
+		/// Named template set instead of the default one
+		#[arg(long)]
+		templates: Option<String>,
+	},
+	/// Send a test message to a Telegram chat or a sink
+	SendTest {
+		/// Telegram chat identifier or sink name
+		chat: String,
+	},
+	/// Print the placeholders of the message templates
+	Placeholders,
+	/// Manage the database
+	Db {
+		#[command(subcommand)]
+		command: DbCommand,
+	},
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DbCommand {
+	/// Apply pending database migrations
+	Migrate,
+	/// Print the number of stored players, games and results
+	Stats,
+}
@@ -0,0 +1,214 @@
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	path::Path,
+};
+
+use super::{
+	achievement, api, bot, config, db, event, message, metrics, notifier, rest, scheduler,
+	supervisor,
+};
+
+pub mod backfill;
+pub mod check_config;
+pub mod database;
+pub mod placeholders;
+pub mod rerender;
+pub mod run;
+pub mod send_test;
+pub mod validate;
+
+pub type Tracked = BTreeMap<(String, api::riot::Platform), BTreeSet<notifier::Chat>>;
+pub type Players<P> = Vec<((P, api::riot::Platform), Vec<notifier::Chat>)>;
+pub type Templates<'c> = (
+	&'c config::riot::lol::Message,
+	&'c config::riot::tft::Message,
+	&'c config::riot::Locale,
+);
+pub type ChatTemplates<'c> = BTreeMap<notifier::Chat, Templates<'c>>;
+
+pub async fn load_config(path: &Path) -> Result<config::Config, String> {
+	let config = config::file::read(path).await?;
+	// With the path of the value at fault, e.g. an unknown field
+	let mut config: config::Config = serde_path_to_error::deserialize(config).map_err(|err| {
+		let at = match err.path().to_string().as_str() {
+			"." => String::from("$"),
+			at => format!("$.{at}"),
+		};
+		format!("Error parsing {}: {at}: {}", path.display(), err.inner())
+	})?;
+	config.resolve_secrets()?;
+	validate::validate(&config).map_err(|problems| {
+		format!(
+			"Invalid configuration {}:\n- {}",
+			path.display(),
+			problems.join("\n- ")
+		)
+	})?;
+	Ok(config)
+}
+
+pub async fn connect_db(url: &str) -> Result<db::Pool, String> {
+	let pool = db::connect(url)
+		.await
+		.map_err(|err| format!("Error opening {url}: {err}"))?;
+	db::migrate(&pool)
+		.await
+		.map_err(|err| format!("Error migrating DB: {err}"))?;
+	Ok(pool)
+}
+
+pub fn riot_apis(config: &config::Config) -> Result<(api::riot::Api, api::riot::Api), String> {
+	Ok((
+		api::riot::Api::new(
+			String::from(config.riot_lol_api_key.expose()),
+			config.riot_lol_rate_limit.parse()?,
+		),
+		api::riot::Api::new(
+			String::from(config.riot_tft_api_key.expose()),
+			config.riot_tft_rate_limit.parse()?,
+		),
+	))
+}
+
+// Get RIOT names-platforms and chats
+pub fn get_tracked(
+	config: &config::Config,
+) -> Result<(Vec<notifier::Chat>, Tracked, Tracked), String> {
+	let mut chats = BTreeSet::default();
+	let mut lol_names_platforms_chats = Tracked::default();
+	let mut tft_names_platforms_chats = Tracked::default();
+	for tracker in &config.trackers {
+		let config::Tracker {
+			riot_lol_platforms_names: lol_platforms_names,
+			riot_tft_platforms_names: tft_platforms_names,
+			..
+		} = tracker;
+		let chat = get_chat(config, tracker)?;
+		chats.insert(chat.clone());
+		for (platform, names) in lol_platforms_names {
+			let platform = parse_platform(platform)?;
+			for name in names {
+				lol_names_platforms_chats
+					.entry((name.clone(), platform))
+					.or_default()
+					.insert(chat.clone());
+			}
+		}
+		for (platform, names) in tft_platforms_names {
+			let platform = parse_platform(platform)?;
+			for name in names {
+				tft_names_platforms_chats
+					.entry((name.clone(), platform))
+					.or_default()
+					.insert(chat.clone());
+			}
+		}
+	}
+	Ok((
+		Vec::from_iter(chats),
+		lol_names_platforms_chats,
+		tft_names_platforms_chats,
+	))
+}
+
+// Named template set, or the default one
+pub fn get_templates<'c>(
+	config: &'c config::Config,
+	name: Option<&String>,
+) -> Result<Templates<'c>, String> {
+	match name {
+		None => Ok((
+			&config.riot_lol_message,
+			&config.riot_tft_message,
+			&config.riot_locale,
+		)),
+		Some(name) => config
+			.templates
+			.get(name)
+			.map(|templates| {
+				(
+					&templates.riot_lol_message,
+					&templates.riot_tft_message,
+					&templates.riot_locale,
+				)
+			})
+			.ok_or_else(|| format!("Unknown template set \"{name}\"")),
+	}
+}
+
+// Template set of every tracking chat: its override, its trackers' or the default
+pub fn get_chat_templates(config: &config::Config) -> Result<ChatTemplates<'_>, String> {
+	let mut chat_templates = ChatTemplates::default();
+	for tracker in &config.trackers {
+		let templates = get_templates(
+			config,
+			tracker
+				.telegram_chat
+				.and_then(|telegram_chat| config.telegram_chat_templates.get(&telegram_chat))
+				.or(tracker.templates.as_ref()),
+		)?;
+		// Trackers of a chat choosing different sets are reported by `validate`
+		chat_templates
+			.entry(get_chat(config, tracker)?)
+			.or_insert(templates);
+	}
+	for name in config.telegram_chat_templates.values() {
+		get_templates(config, Some(name))?;
+	}
+	Ok(chat_templates)
+}
+
+// Telegram chat or named sink of a tracker
+fn get_chat(config: &config::Config, tracker: &config::Tracker) -> Result<notifier::Chat, String> {
+	match (tracker.telegram_chat, &tracker.sink) {
+		(Some(telegram_chat), None) => Ok(notifier::Chat::Telegram(api::telegram::ChatId(
+			telegram_chat,
+		))),
+		(None, Some(sink)) if config.sinks.contains_key(sink) => {
+			Ok(notifier::Chat::Sink(sink.clone()))
+		}
+		(None, Some(sink)) => Err(format!("Unknown sink \"{sink}\"")),
+		_ => Err(String::from(
+			"Every tracker needs either a telegram-chat or a sink",
+		)),
+	}
+}
+
+fn parse_platform(platform: &str) -> Result<api::riot::Platform, String> {
+	platform
+		.to_uppercase()
+		.parse()
+		.map_err(|_| format!("Unknown Riot platform \"{platform}\""))
+}
+
+pub async fn get_lol_players(
+	api: &api::riot::Api,
+	names_platforms_chats: Tracked,
+) -> Result<Players<api::riot::lol::Player>, String> {
+	let mut players_platforms_chats = Vec::default();
+	for ((name, platform), chats) in names_platforms_chats {
+		let player = api::riot::lol::get_player(api, platform, &name)
+			.await
+			.map_err(|err| format!("Error getting LOL player \"{name}\" ({platform}): {err}"))?
+			.ok_or_else(|| format!("LOL player \"{name}\" ({platform}) not found"))?;
+		let chats = Vec::from_iter(chats);
+		players_platforms_chats.push(((player, platform), chats));
+	}
+	Ok(players_platforms_chats)
+}
+
+pub async fn get_tft_players(
+	api: &api::riot::Api,
+	names_platforms_chats: Tracked,
+) -> Result<Players<api::riot::tft::Player>, String> {
+	let mut players_platforms_chats = Vec::default();
+	for ((name, platform), chats) in names_platforms_chats {
+		let player = api::riot::tft::get_player(api, platform, &name)
+			.await
+			.map_err(|err| format!("Error getting TFT player \"{name}\" ({platform}): {err}"))?
+			.ok_or_else(|| format!("TFT player \"{name}\" ({platform}) not found"))?;
+		let chats = Vec::from_iter(chats);
+		players_platforms_chats.push(((player, platform), chats));
+	}
+	Ok(players_platforms_chats)
+}
@@ -0,0 +1,273 @@
+use super::{achievement, api, config, db};
+
+pub async fn backfill(
+	config: config::Config,
+	db_pool: db::Pool,
+	from: chrono::naive::NaiveDate,
+	to: chrono::naive::NaiveDate,
+) -> Result<(), String> {
+	if from > to {
+		return Err(format!("Invalid range: {from} is after {to}"));
+	}
+	let (_, lol_names_platforms_chats, tft_names_platforms_chats) = super::get_tracked(&config)?;
+	let (lol_api, tft_api) = super::riot_apis(&config)?;
+
+	let (lol_players_platforms_chats, tft_players_platforms_chats) = tokio::try_join!(
+		super::get_lol_players(&lol_api, lol_names_platforms_chats),
+		super::get_tft_players(&tft_api, tft_names_platforms_chats),
+	)?;
+	tokio::try_join!(
+		db::riot::lol::insert_players(&db_pool, &lol_players_platforms_chats),
+		db::riot::tft::insert_players(&db_pool, &tft_players_platforms_chats),
+	)
+	.map_err(|err| format!("Error storing players: {err}"))?;
+
+	// Both dates are inclusive
+	let start_time = from.and_hms_opt(0, 0, 0).unwrap().timestamp_millis();
+	let end_time = (to + chrono::Duration::days(1))
+		.and_hms_opt(0, 0, 0)
+		.unwrap()
+		.timestamp_millis();
+
+	let (lol_failed, tft_failed) = tokio::join!(
+		lol(
+			&db_pool,
+			&lol_api,
+			&lol_players_platforms_chats,
+			start_time,
+			end_time,
+			config.store_game_payloads,
+		),
+		tft(
+			&db_pool,
+			&tft_api,
+			&tft_players_platforms_chats,
+			start_time,
+			end_time,
+			config.store_game_payloads,
+		),
+	);
+	let failed = [lol_failed, tft_failed].concat();
+	match failed.is_empty() {
+		true => Ok(()),
+		false => Err(format!(
+			"Error backfilling {}, run again to resume",
+			failed.join(", ")
+		)),
+	}
+}
+
+// Store every game of the tracked players between `start_time` and `end_time`
+// (milliseconds) without notifying. Games already stored are skipped, so an
+// interrupted backfill resumes where it stopped. Returns the players some games
+// of which could not be stored.
+async fn lol<T>(
+	pool: &db::Pool,
+	riot_api: &api::riot::Api,
+	players_platforms_: &[((api::riot::lol::Player, api::riot::Platform), T)],
+	start_time: i64,
+	end_time: i64,
+	store_payload: bool,
+) -> Vec<String> {
+	let mut failed = Vec::default();
+	for ((player, platform), ..) in players_platforms_ {
+		let mut player_failed = false;
+		// Polling starts from now, not from the backfilled games, for players
+		// it has not seen yet
+		if let Ok(None) = db::riot::lol::get_cursor(pool, player).await {
+			db::riot::lol::set_cursor(pool, player, chrono::Utc::now().timestamp_millis())
+				.await
+				.unwrap_or_default();
+		}
+
+		let Ok(game_ids) =
+			api::riot::lol::get_game_ids(riot_api, *platform, player, start_time, Some(end_time))
+				.await
+		else {
+			failed.push(format!(
+				"{} (LOL {})",
+				player.name,
+				platform.as_region_str()
+			));
+			continue;
+		};
+		tracing::info!(
+			platform = platform.as_region_str(),
+			player = player.name,
+			n = game_ids.len(),
+			"Backfilling Riot LOL games"
+		);
+
+		for game_id in game_ids.into_iter().rev() {
+			match db::riot::lol::contains_game(pool, &game_id, *platform).await {
+				Ok(false) => {}
+				Ok(true) => continue,
+				Err(_) => {
+					player_failed = true;
+					continue;
+				}
+			}
+
+			let Ok(Some(game)) = api::riot::lol::get_game(riot_api, *platform, &game_id).await
+			else {
+				player_failed = true;
+				continue;
+			};
+
+			// Leagues are only known at present, so historical games have none
+			let players_participants_leagues = game
+				.info
+				.participants
+				.iter()
+				.filter_map(|participant| {
+					players_platforms_
+						.iter()
+						.find(|((player, ..), ..)| player.puuid == participant.puuid)
+						.map(|((player, ..), ..)| (player, participant, None, ()))
+				})
+				.collect::<Vec<_>>();
+
+			if db::riot::lol::insert_game(
+				pool,
+				&game,
+				*platform,
+				&players_participants_leagues,
+				store_payload,
+			)
+			.await
+			.is_ok()
+			{
+				// Unlocked silently, so they are not announced later
+				achievement::lol::unlock(pool, &game, *platform, &players_participants_leagues)
+					.await;
+			} else {
+				tracing::warn!(game = game_id, "Error storing backfilled Riot LOL game");
+				player_failed = true;
+			}
+		}
+		if player_failed {
+			failed.push(format!(
+				"{} (LOL {})",
+				player.name,
+				platform.as_region_str()
+			));
+		}
+	}
+	failed
+}
+
+async fn tft<T>(
+	pool: &db::Pool,
+	riot_api: &api::riot::Api,
+	players_platforms_: &[((api::riot::tft::Player, api::riot::Platform), T)],
+	start_time: i64,
+	end_time: i64,
+	store_payload: bool,
+) -> Vec<String> {
+	let mut failed = Vec::default();
+	for ((player, platform), ..) in players_platforms_ {
+		let mut player_failed = false;
+		// Polling starts from now, not from the backfilled games, for players
+		// it has not seen yet
+		if let Ok(None) = db::riot::tft::get_cursor(pool, player).await {
+			db::riot::tft::set_cursor(pool, player, chrono::Utc::now().timestamp_millis())
+				.await
+				.unwrap_or_default();
+		}
+
+		let Ok(game_ids) =
+			api::riot::tft::get_game_ids(riot_api, *platform, player, start_time, Some(end_time))
+				.await
+		else {
+			failed.push(format!(
+				"{} (TFT {})",
+				player.name,
+				platform.as_region_str()
+			));
+			continue;
+		};
+		tracing::info!(
+			platform = platform.as_region_str(),
+			player = player.name,
+			n = game_ids.len(),
+			"Backfilling Riot TFT games"
+		);
+
+		for game_id in game_ids.into_iter().rev() {
+			match db::riot::tft::contains_game(pool, &game_id, *platform).await {
+				Ok(false) => {}
+				Ok(true) => continue,
+				Err(_) => {
+					player_failed = true;
+					continue;
+				}
+			}
+
+			let Ok(Some(game)) = api::riot::tft::get_game(riot_api, *platform, &game_id).await
+			else {
+				player_failed = true;
+				continue;
+			};
+
+			// Leagues are only known at present, so historical games have none
+			let players_participants_leagues = game
+				.info
+				.participants
+				.iter()
+				.filter_map(|participant| {
+					players_platforms_
+						.iter()
+						.find(|((player, ..), ..)| player.puuid == participant.puuid)
+						.map(|((player, ..), ..)| (player, participant, None, ()))
+				})
+				.collect::<Vec<_>>();
+
+			if db::riot::tft::insert_game(
+				pool,
+				&game,
+				*platform,
+				&players_participants_leagues,
+				store_payload,
+			)
+			.await
+			.is_ok()
+			{
+				// Unlocked silently, so they are not announced later
+				achievement::tft::unlock(pool, &game, *platform, &players_participants_leagues)
+					.await;
+			} else {
+				tracing::warn!(game = game_id, "Error storing backfilled Riot TFT game");
+				player_failed = true;
+			}
+		}
+		if player_failed {
+			failed.push(format!(
+				"{} (TFT {})",
+				player.name,
+				platform.as_region_str()
+			));
+		}
+	}
+	failed
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn reversed() {
+		let db_pool = sqlx::any::AnyPoolOptions::new()
+			.max_connections(1)
+			.connect("sqlite::memory:")
+			.await
+			.unwrap();
+		let date = |day| chrono::naive::NaiveDate::from_ymd_opt(2024, 5, day).unwrap();
+		assert_eq!(
+			backfill(config::Config::default(), db_pool, date(2), date(1))
+				.await
+				.unwrap_err(),
+			"Invalid range: 2024-05-02 is after 2024-05-01"
+		);
+	}
+}
@@ -0,0 +1,63 @@
+use super::{api, config, message};
+
+pub async fn check_config(config: config::Config) -> Result<(), String> {
+	let (chats, lol_names_platforms_chats, tft_names_platforms_chats) =
+		super::get_tracked(&config)?;
+	super::get_chat_templates(&config)?;
+	println!(
+		"{} chats, {} LOL players, {} TFT players",
+		chats.len(),
+		lol_names_platforms_chats.len(),
+		tft_names_platforms_chats.len()
+	);
+
+	// Resolve every player
+	let (lol_api, tft_api) = super::riot_apis(&config)?;
+	let mut missing = 0;
+	for (name, platform) in lol_names_platforms_chats.into_keys() {
+		match api::riot::lol::get_player(&lol_api, platform, &name).await {
+			Ok(Some(player)) => println!("LOL {platform} {name}: {}", player.puuid),
+			_ => {
+				println!("LOL {platform} {name}: not found");
+				missing += 1;
+			}
+		}
+	}
+	for (name, platform) in tft_names_platforms_chats.into_keys() {
+		match api::riot::tft::get_player(&tft_api, platform, &name).await {
+			Ok(Some(player)) => println!("TFT {platform} {name}: {}", player.puuid),
+			_ => {
+				println!("TFT {platform} {name}: not found");
+				missing += 1;
+			}
+		}
+	}
+
+	// Render every template of every set
+	let sets = [(
+		String::default(),
+		&config.riot_lol_message,
+		&config.riot_tft_message,
+	)]
+	.into_iter()
+	.chain(config.templates.iter().map(|(name, templates)| {
+		(
+			format!("templates.{name}."),
+			&templates.riot_lol_message,
+			&templates.riot_tft_message,
+		)
+	}));
+	for (prefix, lol_message, tft_message) in sets {
+		for (template, sample) in message::riot::lol::generate_samples(lol_message) {
+			println!("\n{prefix}riot-lol-message.{template}:\n{sample}");
+		}
+		for (template, sample) in message::riot::tft::generate_samples(tft_message) {
+			println!("\n{prefix}riot-tft-message.{template}:\n{sample}");
+		}
+	}
+
+	match missing {
+		0 => Ok(()),
+		n => Err(format!("{n} players could not be resolved")),
+	}
+}
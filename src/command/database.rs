@@ -0,0 +1,33 @@
+use super::db;
+
+pub async fn stats(db_pool: db::Pool) -> Result<(), String> {
+	for (product, count) in [
+		("LOL", db::riot::lol::count(&db_pool).await),
+		("TFT", db::riot::tft::count(&db_pool).await),
+	] {
+		let (players, games, results) = count.map_err(|err| format!("Error reading DB: {err}"))?;
+		println!("{product}: {players} players, {games} games, {results} game results");
+	}
+	Ok(())
+}
+
+// Migrations are also applied when connecting for other commands
+pub async fn migrate(url: &str) -> Result<(), String> {
+	let db_pool = db::connect(url)
+		.await
+		.map_err(|err| format!("Error opening {url}: {err}"))?;
+	let applied = db::migrate(&db_pool)
+		.await
+		.map_err(|err| format!("Error migrating DB: {err}"))?;
+	for (version, name) in &applied {
+		println!("Applied migration {version} ({name})");
+	}
+	if applied.is_empty() {
+		println!("No pending migrations");
+	}
+	let version = db::migration::get_version(&db_pool)
+		.await
+		.map_err(|err| format!("Error reading DB: {err}"))?;
+	println!("Database schema at version {version}");
+	Ok(())
+}
@@ -0,0 +1,33 @@
+use super::message::riot::placeholders::PLACEHOLDERS;
+
+pub fn placeholders() {
+	for placeholder in &PLACEHOLDERS {
+		println!(
+			"{{{}}} ({})\n  {}",
+			placeholder.name,
+			placeholder.products.join(", "),
+			placeholder.description
+		);
+		if !placeholder.aliases.is_empty() {
+			println!(
+				"  Also written: {}",
+				placeholder
+					.aliases
+					.iter()
+					.map(|alias| format!("{{{alias}}}"))
+					.collect::<Vec<_>>()
+					.join(" ")
+			);
+		}
+		println!(
+			"  Templates: {}",
+			placeholder
+				.kinds
+				.iter()
+				.map(|kind| kind.as_str())
+				.collect::<Vec<_>>()
+				.join(", ")
+		);
+		println!("  Example: {}", placeholder.example.escape_debug());
+	}
+}
@@ -0,0 +1,47 @@
+use super::{config, db, message};
+
+// Print the messages of a stored game, rendered with the current templates
+pub async fn rerender(
+	config: config::Config,
+	db_pool: db::Pool,
+	game_id: String,
+	templates: Option<String>,
+) -> Result<(), String> {
+	let (lol_message, tft_message, locale) = super::get_templates(&config, templates.as_ref())?;
+	let read_error = |err| format!("Error reading game {game_id}: {err}");
+
+	let messages = if let Some((game, platform, players_participants_leagues_streaks)) =
+		db::riot::lol::get_game(&db_pool, &game_id)
+			.await
+			.map_err(read_error)?
+	{
+		message::riot::lol::generate_messages(
+			&game,
+			platform,
+			&players_participants_leagues_streaks,
+			lol_message,
+			locale,
+		)
+	} else if let Some((game, platform, players_participants_leagues_streaks)) =
+		db::riot::tft::get_game(&db_pool, &game_id)
+			.await
+			.map_err(read_error)?
+	{
+		message::riot::tft::generate_messages(
+			&game,
+			platform,
+			&players_participants_leagues_streaks,
+			tft_message,
+			locale,
+		)
+	} else {
+		return Err(format!(
+			"Game {game_id} is not stored with its payload (see store-game-payloads)"
+		));
+	};
+
+	for message in messages {
+		println!("{message}\n");
+	}
+	Ok(())
+}
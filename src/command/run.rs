@@ -0,0 +1,964 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{
+	achievement, api, bot, config, db, event, message, metrics,
+	notifier::{self, Notifier},
+	rest, scheduler, supervisor,
+};
+
+// From the shutdown signal, within the 10 seconds container runtimes wait by
+// default
+const SHUTDOWN_TIMEOUT: tokio::time::Duration = tokio::time::Duration::from_secs(8);
+
+// Attempts at sending a message before it is dropped
+const MAX_MESSAGE_ATTEMPTS: u32 = 5;
+
+// Attempts at getting and storing a game before polling moves past it
+const MAX_GAME_ATTEMPTS: u32 = 3;
+
+// Failed attempts by game identifier. Polling moves the cursors past the games
+// given up on, so that one that can never be stored does not hold back newer ones.
+#[derive(Default)]
+struct FailedGames(std::sync::Mutex<BTreeMap<String, u32>>);
+
+impl FailedGames {
+	// Games not found are given up on at once
+	fn fail(&self, product: &str, game_id: &str, not_found: bool) {
+		let mut failed = self.0.lock().unwrap();
+		let attempts = failed.entry(String::from(game_id)).or_default();
+		*attempts = match not_found {
+			true => MAX_GAME_ATTEMPTS,
+			false => *attempts + 1,
+		};
+		if *attempts == MAX_GAME_ATTEMPTS {
+			tracing::warn!(product, game_id, not_found, "Giving up on Riot game");
+		}
+	}
+
+	fn given_up(&self) -> BTreeSet<String> {
+		self.0
+			.lock()
+			.unwrap()
+			.iter()
+			.filter(|(_, attempts)| **attempts >= MAX_GAME_ATTEMPTS)
+			.map(|(game_id, _)| game_id.clone())
+			.collect()
+	}
+}
+
+// Kept for the rest of the process, as the application runs once
+fn leak<T>(value: T) -> &'static T {
+	Box::leak(Box::new(value))
+}
+
+pub async fn run(config: config::Config, db_pool: db::Pool) -> Result<(), String> {
+	let config = leak(config);
+	let (chats, lol_names_platforms_chats, tft_names_platforms_chats) = super::get_tracked(config)?;
+	let chat_templates = super::get_chat_templates(config)?;
+	let sinks = notifier::sinks(config, &chats)?;
+	let rest_api = config
+		.rest_api
+		.as_ref()
+		.map(|rest_api| rest::bind(&rest_api.listen))
+		.transpose()?;
+	let metrics_server = config
+		.metrics
+		.as_ref()
+		.map(|metrics| rest::bind(&metrics.listen))
+		.transpose()?;
+
+	// RIOT API instances
+	let (lol_api, tft_api) = super::riot_apis(config)?;
+
+	// Tracked players of the REST API, before being looked up
+	let rest_names_platforms_chats = (
+		lol_names_platforms_chats.clone(),
+		tft_names_platforms_chats.clone(),
+	);
+
+	// Get players
+	let (lol_players_platforms_chats, tft_players_platforms_chats) = tokio::try_join!(
+		super::get_lol_players(&lol_api, lol_names_platforms_chats),
+		super::get_tft_players(&tft_api, tft_names_platforms_chats),
+	)?;
+
+	// Store players in DB
+	tokio::try_join!(
+		db::riot::lol::insert_players(&db_pool, &lol_players_platforms_chats),
+		db::riot::tft::insert_players(&db_pool, &tft_players_platforms_chats),
+	)
+	.map_err(|err| format!("Error storing players: {err}"))?;
+
+	// On shutdown, polling stops at once, processing finishes its current game
+	// and then notifying sends what is pending
+	let (polling, processing, notifying) = (
+		leak(supervisor::Supervisor::default()),
+		leak(supervisor::Supervisor::default()),
+		leak(supervisor::Supervisor::default()),
+	);
+
+	// Shared by the tasks, which are started again from these on restarts. They
+	// are spawned, so what they share lives until the process exits.
+	let (db_pool, lol_api, tft_api) = (leak(db_pool), leak(lol_api), leak(tft_api));
+	let (chats, chat_templates, sinks) = (leak(chats), leak(chat_templates), leak(sinks));
+	let (lol_players_platforms_chats, tft_players_platforms_chats) = (
+		leak(lol_players_platforms_chats),
+		leak(tft_players_platforms_chats),
+	);
+
+	// LOL game identifiers getter task
+	let (lol_game_ids_sender, lol_game_ids_receiver) = tokio::sync::mpsc::channel(128);
+	let (lol_game_ids_sender, lol_game_ids_receiver) = (
+		leak(lol_game_ids_sender),
+		leak(tokio::sync::Mutex::new(lol_game_ids_receiver)),
+	);
+	// Game identifiers waiting in the channel, not queued again by later polls
+	let lol_queued_game_ids = leak(std::sync::Mutex::new(BTreeSet::default()));
+	let lol_failed_games = leak(FailedGames::default());
+	// Tracked players of every stored game, by index, with when it ended
+	let (lol_played_sender, lol_played_receiver) = tokio::sync::mpsc::unbounded_channel();
+	let (lol_played_sender, lol_played_receiver) = (
+		leak(lol_played_sender),
+		leak(tokio::sync::Mutex::new(lol_played_receiver)),
+	);
+	let lol_get_game_ids = move || async move {
+		let mut last_games = Vec::default();
+		for ((player, ..), ..) in lol_players_platforms_chats {
+			let last_game = db::riot::lol::get_last_game_timestamp(db_pool, player)
+				.await
+				.unwrap_or_default();
+			// Start tracking new players from now on
+			if let Ok(None) = db::riot::lol::get_cursor(db_pool, player).await {
+				let cursor = last_game
+					.map(|last_game| last_game + 1000)
+					.unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+				db::riot::lol::set_cursor(db_pool, player, cursor)
+					.await
+					.unwrap_or_default();
+			}
+			last_games.push(last_game);
+		}
+		let mut scheduler = scheduler::Scheduler::new(&config.riot_lol_polling, last_games);
+		let mut lol_played_receiver = lol_played_receiver.lock().await;
+		loop {
+			let index = tokio::select! {
+				index = scheduler.next() => index,
+				Some((index, game_end)) = lol_played_receiver.recv() => {
+					scheduler.played(index, game_end);
+					continue;
+				}
+			};
+			let _cycle = metrics::PollingCycle::start("lol");
+			let ((player, platform), ..) = &lol_players_platforms_chats[index];
+
+			if config.riot_lol_polling.spectator
+				&& api::riot::lol::is_playing(lol_api, *platform, player)
+					.await
+					.unwrap_or_default()
+			{
+				scheduler.reschedule(index, scheduler::Activity::Playing);
+				continue;
+			}
+
+			let Ok(Some(cursor)) = db::riot::lol::get_cursor(db_pool, player).await else {
+				scheduler.reschedule(index, scheduler::Activity::Idle);
+				continue;
+			};
+			let Ok(game_ids) =
+				api::riot::lol::get_game_ids(lol_api, *platform, player, cursor, None).await
+			else {
+				scheduler.reschedule(index, scheduler::Activity::Idle);
+				continue;
+			};
+			// Only games neither stored nor given up on, the cursor stops before the oldest
+			let Ok(game_ids) = db::riot::lol::advance_cursor(
+				db_pool,
+				player,
+				*platform,
+				&game_ids,
+				&lol_failed_games.given_up(),
+			)
+			.await
+			else {
+				scheduler.reschedule(index, scheduler::Activity::Idle);
+				continue;
+			};
+
+			// New games bring the next poll forward once stored
+			scheduler.reschedule(index, scheduler::Activity::Idle);
+
+			for game_id in game_ids {
+				if !lol_queued_game_ids.lock().unwrap().insert(game_id.clone()) {
+					continue;
+				}
+				match lol_game_ids_sender.send((game_id, *platform)).await {
+					Ok(()) => metrics::queued("lol_game_ids", 1),
+					Err(err) => tracing::error!(
+						error = err.to_string(),
+						"Error sending LOL game identifier to channel"
+					),
+				}
+			}
+			tokio::task::yield_now().await;
+		}
+	};
+
+	// TFT game identifiers getter task
+	let (tft_game_ids_sender, tft_game_ids_receiver) = tokio::sync::mpsc::channel(128);
+	let (tft_game_ids_sender, tft_game_ids_receiver) = (
+		leak(tft_game_ids_sender),
+		leak(tokio::sync::Mutex::new(tft_game_ids_receiver)),
+	);
+	// Game identifiers waiting in the channel, not queued again by later polls
+	let tft_queued_game_ids = leak(std::sync::Mutex::new(BTreeSet::default()));
+	let tft_failed_games = leak(FailedGames::default());
+	// Tracked players of every stored game, by index, with when it ended
+	let (tft_played_sender, tft_played_receiver) = tokio::sync::mpsc::unbounded_channel();
+	let (tft_played_sender, tft_played_receiver) = (
+		leak(tft_played_sender),
+		leak(tokio::sync::Mutex::new(tft_played_receiver)),
+	);
+	let tft_get_game_ids = move || async move {
+		let mut last_games = Vec::default();
+		for ((player, ..), ..) in tft_players_platforms_chats {
+			let last_game = db::riot::tft::get_last_game_timestamp(db_pool, player)
+				.await
+				.unwrap_or_default();
+			// Start tracking new players from now on
+			if let Ok(None) = db::riot::tft::get_cursor(db_pool, player).await {
+				let cursor = last_game
+					.map(|last_game| last_game + 1000)
+					.unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+				db::riot::tft::set_cursor(db_pool, player, cursor)
+					.await
+					.unwrap_or_default();
+			}
+			last_games.push(last_game);
+		}
+		let mut scheduler = scheduler::Scheduler::new(&config.riot_tft_polling, last_games);
+		let mut tft_played_receiver = tft_played_receiver.lock().await;
+		loop {
+			let index = tokio::select! {
+				index = scheduler.next() => index,
+				Some((index, game_end)) = tft_played_receiver.recv() => {
+					scheduler.played(index, game_end);
+					continue;
+				}
+			};
+			let _cycle = metrics::PollingCycle::start("tft");
+			let ((player, platform), ..) = &tft_players_platforms_chats[index];
+
+			if config.riot_tft_polling.spectator
+				&& api::riot::tft::is_playing(tft_api, *platform, player)
+					.await
+					.unwrap_or_default()
+			{
+				scheduler.reschedule(index, scheduler::Activity::Playing);
+				continue;
+			}
+
+			let Ok(Some(cursor)) = db::riot::tft::get_cursor(db_pool, player).await else {
+				scheduler.reschedule(index, scheduler::Activity::Idle);
+				continue;
+			};
+			let Ok(game_ids) =
+				api::riot::tft::get_game_ids(tft_api, *platform, player, cursor, None).await
+			else {
+				scheduler.reschedule(index, scheduler::Activity::Idle);
+				continue;
+			};
+			// Only games neither stored nor given up on, the cursor stops before the oldest
+			let Ok(game_ids) = db::riot::tft::advance_cursor(
+				db_pool,
+				player,
+				*platform,
+				&game_ids,
+				&tft_failed_games.given_up(),
+			)
+			.await
+			else {
+				scheduler.reschedule(index, scheduler::Activity::Idle);
+				continue;
+			};
+
+			// New games bring the next poll forward once stored
+			scheduler.reschedule(index, scheduler::Activity::Idle);
+
+			for game_id in game_ids {
+				if !tft_queued_game_ids.lock().unwrap().insert(game_id.clone()) {
+					continue;
+				}
+				match tft_game_ids_sender.send((game_id, *platform)).await {
+					Ok(()) => metrics::queued("tft_game_ids", 1),
+					Err(err) => tracing::error!(
+						error = err.to_string(),
+						"Error sending TFT game identifier to channel"
+					),
+				}
+			}
+			tokio::task::yield_now().await;
+		}
+	};
+
+	let (messages_sender, messages_receiver) = tokio::sync::mpsc::unbounded_channel();
+	let (messages_sender, messages_receiver) = (
+		leak(messages_sender),
+		leak(tokio::sync::Mutex::new(messages_receiver)),
+	);
+	let (events_sender, events_receiver) = tokio::sync::mpsc::unbounded_channel();
+	let (events_sender, events_receiver) = (
+		leak(events_sender),
+		leak(tokio::sync::Mutex::new(events_receiver)),
+	);
+	let events = config.events.file.is_some() || !config.events.webhooks.is_empty();
+	// When pending games, messages and events must be done by, set on shutdown
+	let shutdown_deadline = leak(std::sync::OnceLock::new());
+
+	// LOL game getter task
+	let lol_get_games = move || async move {
+		let mut lol_game_ids_receiver = lol_game_ids_receiver.lock().await;
+		while let Some(received) = processing.until_stopped(lol_game_ids_receiver.recv()).await {
+			// Queued games are polled again by the next run
+			if processing.is_stopped() {
+				return;
+			}
+			let Some((game_id, platform)) = received else {
+				tracing::error!("Riot LOL game identifier receiver has closed unexpectedly");
+				return;
+			};
+			metrics::queued("lol_game_ids", -1);
+			lol_queued_game_ids.lock().unwrap().remove(&game_id);
+			if db::riot::lol::contains_game(db_pool, &game_id, platform)
+				.await
+				.unwrap_or(true)
+			{
+				continue;
+			}
+
+			let game = match api::riot::lol::get_game(lol_api, platform, &game_id).await {
+				Ok(Some(game)) => game,
+				Ok(None) => {
+					lol_failed_games.fail("lol", &game_id, true);
+					continue;
+				}
+				Err(_) => {
+					lol_failed_games.fail("lol", &game_id, false);
+					continue;
+				}
+			};
+
+			let players_participants_chats =
+				game.info.participants.iter().filter_map(|participant| {
+					lol_players_platforms_chats
+						.iter()
+						.find(|((player, ..), ..)| player.puuid == participant.puuid)
+						.map(|((player, ..), chats)| (player, participant, chats))
+				});
+
+			let mut players_participants_leagues_chats = Vec::default();
+			for (player, participant, chats) in players_participants_chats {
+				let league = api::riot::lol::get_leagues(lol_api, platform, player)
+					.await
+					.unwrap_or_default()
+					.into_iter()
+					.find(|league| {
+						api::riot::are_same_queue(&league.queue_type, game.info.queue_id)
+					});
+
+				players_participants_leagues_chats.push((player, participant, league, chats));
+			}
+			let players_participants_leagues_chats = players_participants_leagues_chats;
+
+			if db::riot::lol::insert_game(
+				db_pool,
+				&game,
+				platform,
+				&players_participants_leagues_chats,
+				config.store_game_payloads,
+			)
+			.await
+			.is_err()
+			{
+				lol_failed_games.fail("lol", &game_id, false);
+				continue;
+			}
+			metrics::game_stored("lol");
+
+			if events {
+				let event = event::lol::generate(
+					db_pool,
+					&game,
+					platform,
+					&players_participants_leagues_chats,
+				)
+				.await;
+				events_sender.send(event).unwrap_or_else(|err| {
+					tracing::error!(
+						error = err.to_string(),
+						"Error sending event (LOL) to channel"
+					)
+				});
+			}
+
+			let game_end = game
+				.info
+				.game_end_timestamp
+				.unwrap_or(game.info.game_start_timestamp);
+
+			// Players who have just played are polled sooner
+			for (player, ..) in &players_participants_leagues_chats {
+				let Some(index) = lol_players_platforms_chats
+					.iter()
+					.position(|((tracked, ..), ..)| tracked.puuid == player.puuid)
+				else {
+					continue;
+				};
+				lol_played_sender
+					.send((index, game_end))
+					.unwrap_or_else(|err| {
+						tracing::error!(
+							error = err.to_string(),
+							"Error sending played game (LOL) to channel"
+						)
+					});
+			}
+
+			// Streaks include the game just stored
+			let mut streaks = Vec::default();
+			for (player, ..) in &players_participants_leagues_chats {
+				streaks.push(
+					db::riot::lol::get_streak(db_pool, player, &game)
+						.await
+						.unwrap_or_default(),
+				);
+			}
+			// Achievements are only announced when first unlocked
+			let achievements = achievement::lol::unlock(
+				db_pool,
+				&game,
+				platform,
+				&players_participants_leagues_chats,
+			)
+			.await;
+
+			for chat in chats {
+				let (lol_message, _, locale) = chat_templates[chat];
+				let players_participants_leagues_streaks = players_participants_leagues_chats
+					.iter()
+					.zip(&streaks)
+					.filter(|((.., chats), _)| chats.contains(chat))
+					.map(|((player, participant, league, ..), streak)| {
+						(
+							(*player).clone(),
+							(*participant).clone(),
+							league.clone(),
+							*streak,
+						)
+					})
+					.collect::<Vec<_>>();
+				// Cards fall back to text messages
+				let card = if config.image_messages {
+					message::riot::card::generate_lol(
+						&game,
+						platform,
+						&players_participants_leagues_streaks,
+						locale,
+					)
+					.inspect_err(|err| tracing::error!(error = err, "Error generating card"))
+					.ok()
+				} else {
+					None
+				};
+				let mut messages = match card {
+					Some((card, caption)) => vec![message::Message::photo(card, &caption)],
+					None => message::riot::lol::generate_messages(
+						&game,
+						platform,
+						&players_participants_leagues_streaks,
+						lol_message,
+						locale,
+					)
+					.into_iter()
+					.map(|text| message::Message::html(&text))
+					.collect(),
+				};
+				messages.extend(
+					players_participants_leagues_chats
+						.iter()
+						.zip(&achievements)
+						.filter(|((.., chats), _)| chats.contains(chat))
+						.flat_map(|((player, ..), achievements)| {
+							message::riot::lol::generate_achievement_messages(
+								&game,
+								platform,
+								player,
+								achievements,
+								lol_message,
+								locale,
+							)
+						})
+						.map(|text| message::Message::html(&text)),
+				);
+				for message in messages {
+					let queued =
+						notifier::Queued::new(chat.clone(), message, Some(("lol", game_end)));
+					match messages_sender.send(queued) {
+						Ok(()) => metrics::queued("messages", 1),
+						Err(err) => tracing::error!(
+							error = err.to_string(),
+							"Error sending message (LOL) to channel"
+						),
+					}
+				}
+			}
+		}
+	};
+
+	// TFT game getter task
+	let tft_get_games = move || async move {
+		let mut tft_game_ids_receiver = tft_game_ids_receiver.lock().await;
+		while let Some(received) = processing.until_stopped(tft_game_ids_receiver.recv()).await {
+			// Queued games are polled again by the next run
+			if processing.is_stopped() {
+				return;
+			}
+			let Some((game_id, platform)) = received else {
+				tracing::error!("Riot TFT game identifier receiver has closed unexpectedly");
+				return;
+			};
+			metrics::queued("tft_game_ids", -1);
+			tft_queued_game_ids.lock().unwrap().remove(&game_id);
+			if db::riot::tft::contains_game(db_pool, &game_id, platform)
+				.await
+				.unwrap_or(true)
+			{
+				continue;
+			}
+
+			let game = match api::riot::tft::get_game(tft_api, platform, &game_id).await {
+				Ok(Some(game)) => game,
+				Ok(None) => {
+					tft_failed_games.fail("tft", &game_id, true);
+					continue;
+				}
+				Err(_) => {
+					tft_failed_games.fail("tft", &game_id, false);
+					continue;
+				}
+			};
+
+			let players_participants_chats =
+				game.info.participants.iter().filter_map(|participant| {
+					tft_players_platforms_chats
+						.iter()
+						.find(|((player, ..), ..)| player.puuid == participant.puuid)
+						.map(|((player, ..), chats)| (player, participant, chats))
+				});
+
+			let mut players_participants_leagues_chats = Vec::default();
+			for (player, participant, chats) in players_participants_chats {
+				let league = api::riot::tft::get_leagues(tft_api, platform, player)
+					.await
+					.unwrap_or_default()
+					.into_iter()
+					.find(|league| {
+						api::riot::are_same_queue_id(&league.queue_type, game.info.queue_id)
+					});
+
+				players_participants_leagues_chats.push((player, participant, league, chats));
+			}
+			let players_participants_leagues_chats = players_participants_leagues_chats;
+
+			if db::riot::tft::insert_game(
+				db_pool,
+				&game,
+				platform,
+				&players_participants_leagues_chats,
+				config.store_game_payloads,
+			)
+			.await
+			.is_err()
+			{
+				tft_failed_games.fail("tft", &game_id, false);
+				continue;
+			}
+			metrics::game_stored("tft");
+
+			if events {
+				let event = event::tft::generate(
+					db_pool,
+					&game,
+					platform,
+					&players_participants_leagues_chats,
+				)
+				.await;
+				events_sender.send(event).unwrap_or_else(|err| {
+					tracing::error!(
+						error = err.to_string(),
+						"Error sending event (TFT) to channel"
+					)
+				});
+			}
+
+			let game_end = game.info.game_datetime;
+
+			// Players who have just played are polled sooner
+			for (player, ..) in &players_participants_leagues_chats {
+				let Some(index) = tft_players_platforms_chats
+					.iter()
+					.position(|((tracked, ..), ..)| tracked.puuid == player.puuid)
+				else {
+					continue;
+				};
+				tft_played_sender
+					.send((index, game_end))
+					.unwrap_or_else(|err| {
+						tracing::error!(
+							error = err.to_string(),
+							"Error sending played game (TFT) to channel"
+						)
+					});
+			}
+
+			// Streaks include the game just stored
+			let mut streaks = Vec::default();
+			for (player, ..) in &players_participants_leagues_chats {
+				streaks.push(
+					db::riot::tft::get_streak(db_pool, player, &game)
+						.await
+						.unwrap_or_default(),
+				);
+			}
+			// Achievements are only announced when first unlocked
+			let achievements = achievement::tft::unlock(
+				db_pool,
+				&game,
+				platform,
+				&players_participants_leagues_chats,
+			)
+			.await;
+
+			for chat in chats {
+				let (_, tft_message, locale) = chat_templates[chat];
+				let players_participants_leagues_streaks = players_participants_leagues_chats
+					.iter()
+					.zip(&streaks)
+					.filter(|((.., chats), _)| chats.contains(chat))
+					.map(|((player, participant, league, ..), streak)| {
+						(
+							(*player).clone(),
+							(*participant).clone(),
+							league.clone(),
+							*streak,
+						)
+					})
+					.collect::<Vec<_>>();
+
+				// Cards fall back to text messages
+				let card = if config.image_messages {
+					message::riot::card::generate_tft(
+						&game,
+						&players_participants_leagues_streaks,
+						locale,
+					)
+					.inspect_err(|err| tracing::error!(error = err, "Error generating card"))
+					.ok()
+				} else {
+					None
+				};
+				let mut messages = match card {
+					Some((card, caption)) => vec![message::Message::photo(card, &caption)],
+					None => message::riot::tft::generate_messages(
+						&game,
+						platform,
+						&players_participants_leagues_streaks,
+						tft_message,
+						locale,
+					)
+					.into_iter()
+					.map(|text| message::Message::html(&text))
+					.collect(),
+				};
+				messages.extend(
+					players_participants_leagues_chats
+						.iter()
+						.zip(&achievements)
+						.filter(|((.., chats), _)| chats.contains(chat))
+						.flat_map(|((player, participant, ..), achievements)| {
+							message::riot::tft::generate_achievement_messages(
+								&game,
+								platform,
+								(player, participant),
+								achievements,
+								tft_message,
+								locale,
+							)
+						})
+						.map(|text| message::Message::html(&text)),
+				);
+				for message in messages {
+					let queued =
+						notifier::Queued::new(chat.clone(), message, Some(("tft", game_end)));
+					match messages_sender.send(queued) {
+						Ok(()) => metrics::queued("messages", 1),
+						Err(err) => tracing::error!(
+							error = err.to_string(),
+							"Error sending message (TFT) to channel"
+						),
+					}
+				}
+			}
+		}
+	};
+
+	// Telegram bot commands task, idle without Telegram chats
+	let telegram_commands = move || async move {
+		if !config.uses_telegram() {
+			return std::future::pending().await;
+		}
+		let telegram_api = api::telegram::Api::new(config.telegram_api_key.expose());
+		let mut offset = 0;
+		loop {
+			let Ok((messages, next_offset)) =
+				api::telegram::get_messages(&telegram_api, offset).await
+			else {
+				tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+				continue;
+			};
+			offset = next_offset;
+
+			for (telegram_chat, text) in messages {
+				// Only tracking chats may ask
+				let chat = notifier::Chat::Telegram(telegram_chat);
+				if !chats.contains(&chat) {
+					continue;
+				}
+				let Some(command) = bot::parse(&text) else {
+					continue;
+				};
+				let answer = match command {
+					Ok(command) => {
+						bot::answer(
+							command,
+							&chat,
+							db_pool,
+							lol_players_platforms_chats,
+							tft_players_platforms_chats,
+							chat_templates[&chat].2,
+						)
+						.await
+					}
+					Err(usage) => message::Message::html(usage),
+				};
+				match messages_sender.send(notifier::Queued::new(chat, answer, None)) {
+					Ok(()) => metrics::queued("messages", 1),
+					Err(err) => tracing::error!(
+						error = err.to_string(),
+						"Error sending Telegram answer to channel"
+					),
+				}
+			}
+		}
+	};
+
+	// Notifier task, failed messages are sent again after a second, unless
+	// rejected by their sink or failed too many times
+	let notify = move || async move {
+		let mut messages_receiver = messages_receiver.lock().await;
+		while let Some(received) = notifying.until_stopped(messages_receiver.recv()).await {
+			let Some(mut queued) = received else {
+				tracing::error!("Message receiver has closed unexpectedly");
+				return;
+			};
+			metrics::queued("messages", -1);
+			let sink = &sinks[&queued.chat];
+			let sent = sink.send(&queued.message, queued.id).await;
+			metrics::message_sent(sink.kind(), sent.is_ok());
+			queued.attempts += 1;
+			match sent {
+				Ok(()) => {
+					if let Some((product, game_end)) = queued.game_end {
+						metrics::notified(product, game_end);
+					}
+					continue;
+				}
+				Err(notifier::Error::Rejected(_)) => {
+					tracing::warn!(sink = sink.kind(), "Dropping message rejected by its sink");
+					continue;
+				}
+				Err(notifier::Error::Failed(_)) if queued.attempts >= MAX_MESSAGE_ATTEMPTS => {
+					tracing::warn!(
+						sink = sink.kind(),
+						attempts = queued.attempts,
+						"Dropping message after failed attempts"
+					);
+					continue;
+				}
+				Err(notifier::Error::Failed(_)) => {}
+			}
+			tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+			match messages_sender.send(queued) {
+				Ok(()) => metrics::queued("messages", 1),
+				Err(err) => tracing::error!(
+					error = err.to_string(),
+					"Error resending message to channel"
+				),
+			}
+		}
+	};
+
+	// Game events task, events still queued at shutdown are delivered without
+	// retrying past the shutdown timeout
+	let deliver_events = move || async move {
+		let mut events_receiver = events_receiver.lock().await;
+		let client = reqwest::Client::new();
+		while let Some(received) = notifying.until_stopped(events_receiver.recv()).await {
+			let Some(event) = received else {
+				tracing::error!("Event receiver has closed unexpectedly");
+				return;
+			};
+			event::deliver(
+				&client,
+				&config.events,
+				&event,
+				shutdown_deadline.get().copied(),
+			)
+			.await;
+		}
+		while let Ok(event) = events_receiver.try_recv() {
+			event::deliver(
+				&client,
+				&config.events,
+				&event,
+				shutdown_deadline.get().copied(),
+			)
+			.await;
+		}
+	};
+
+	// Riot API rate budget reporter task
+	let riot_report = move || async move {
+		let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(60));
+		interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+		interval.tick().await;
+		loop {
+			interval.tick().await;
+			lol_api.budget().report("lol");
+			tft_api.budget().report("tft");
+		}
+	};
+
+	// REST API server task, idle without its configuration. Restarts listen again.
+	let rest_api = leak(std::sync::Mutex::new(rest_api));
+	let rest_names_platforms_chats = leak(rest_names_platforms_chats);
+	let serve_rest_api = move || async move {
+		let Some(config_rest_api) = &config.rest_api else {
+			return std::future::pending().await;
+		};
+		let builder = rest_api
+			.lock()
+			.unwrap()
+			.take()
+			.map_or_else(|| rest::bind(&config_rest_api.listen), Ok);
+		let served = match builder {
+			Ok(builder) => {
+				rest::serve(
+					builder,
+					String::from(config_rest_api.token.expose()),
+					db_pool.clone(),
+					rest_names_platforms_chats.0.clone(),
+					rest_names_platforms_chats.1.clone(),
+				)
+				.await
+			}
+			Err(err) => Err(err),
+		};
+		served.unwrap_or_else(|err| tracing::error!(error = err, "Error serving REST API"));
+	};
+
+	// Metrics server task, idle without its configuration. Restarts listen again.
+	let metrics_server = leak(std::sync::Mutex::new(metrics_server));
+	let serve_metrics = move || async move {
+		let Some(config_metrics) = &config.metrics else {
+			return std::future::pending().await;
+		};
+		let builder = metrics_server
+			.lock()
+			.unwrap()
+			.take()
+			.map_or_else(|| rest::bind(&config_metrics.listen), Ok);
+		let served = match builder {
+			Ok(builder) => metrics::serve(builder).await,
+			Err(err) => Err(err),
+		};
+		served.unwrap_or_else(|err| tracing::error!(error = err, "Error serving metrics"));
+	};
+
+	// Run tasks until CTRL-C or SIGTERM, restarting them when they end
+	tokio::join!(
+		polling.supervise("riot_report", riot_report),
+		polling.supervise("lol_get_game_ids", lol_get_game_ids),
+		polling.supervise("tft_get_game_ids", tft_get_game_ids),
+		polling.supervise("telegram_commands", telegram_commands),
+		polling.supervise("serve_rest_api", serve_rest_api),
+		polling.supervise("serve_metrics", serve_metrics),
+		async {
+			let process = async {
+				tokio::join!(
+					processing.supervise_draining("lol_get_games", lol_get_games),
+					processing.supervise_draining("tft_get_games", tft_get_games),
+				);
+				notifying.shutdown();
+			};
+			let notify = async {
+				tokio::join!(
+					notifying.supervise_draining("notify", notify),
+					notifying.supervise_draining("deliver_events", deliver_events),
+				)
+			};
+			let timeout = async {
+				polling.stopped().await;
+				match shutdown_deadline.get() {
+					Some(deadline) => tokio::time::sleep_until(*deadline).await,
+					None => tokio::time::sleep(SHUTDOWN_TIMEOUT).await,
+				}
+			};
+			tokio::select! {
+				_ = async { tokio::join!(process, notify) } => {},
+				_ = timeout => tracing::warn!("Timed out finishing pending games and messages"),
+			}
+		},
+		async {
+			supervisor::shutdown_signal().await;
+			shutdown_deadline.get_or_init(|| tokio::time::Instant::now() + SHUTDOWN_TIMEOUT);
+			polling.shutdown();
+			processing.shutdown();
+		},
+	);
+
+	tracing::debug!("Exiting");
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn failed_games() {
+		let failed_games = FailedGames::default();
+		for _ in 1..MAX_GAME_ATTEMPTS {
+			failed_games.fail("lol", "EUW1_1", false);
+		}
+		failed_games.fail("lol", "EUW1_2", true);
+		assert_eq!(
+			failed_games.given_up(),
+			BTreeSet::from([String::from("EUW1_2")])
+		);
+		failed_games.fail("lol", "EUW1_1", false);
+		assert_eq!(
+			failed_games.given_up(),
+			BTreeSet::from([String::from("EUW1_1"), String::from("EUW1_2")])
+		);
+	}
+}
@@ -0,0 +1,17 @@
+use super::{config, message, notifier, notifier::Notifier};
+
+// Telegram chat identifier or sink name
+pub async fn send_test(config: config::Config, chat: String) -> Result<(), String> {
+	let chat = match chat.parse() {
+		Ok(telegram_chat) => notifier::Chat::Telegram(super::api::telegram::ChatId(telegram_chat)),
+		Err(_) => notifier::Chat::Sink(chat),
+	};
+	let sinks = notifier::sinks(&config, std::slice::from_ref(&chat))?;
+	sinks[&chat]
+		.send(
+			&message::Message::html("✅ <b>telegram-riot</b> test message"),
+			0,
+		)
+		.await
+		.map_err(|err| format!("Error sending test message: {err}"))
+}
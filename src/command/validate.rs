@@ -0,0 +1,252 @@
+use std::collections::{btree_map::Entry, BTreeMap};
+
+use super::{api, config, message, notifier};
+
+// Seconds, so that poll times stay far from overflowing
+const MAX_POLLING_INTERVAL: u64 = 7 * 24 * 3600;
+
+// Every problem of a configuration, each with its JSON path, beyond what
+// parsing it already checks
+pub fn validate(config: &config::Config) -> Result<(), Vec<String>> {
+	let mut problems = Vec::default();
+
+	// Players of a product by chat, platform and case-insensitive name, with
+	// where they were first listed
+	let mut lol_players = BTreeMap::default();
+	let mut tft_players = BTreeMap::default();
+	// Template set of every chat not in telegram-chat-templates, with the
+	// tracker that first chose it
+	let mut chats_templates = BTreeMap::default();
+	for (index, tracker) in config.trackers.iter().enumerate() {
+		let path = format!("$.trackers[{index}]");
+		let chat = match (tracker.telegram_chat, &tracker.sink) {
+			(Some(0), None) => {
+				problems.push(format!("{path}.telegram-chat: invalid Telegram chat id 0"));
+				None
+			}
+			(Some(telegram_chat), None) => Some(notifier::Chat::Telegram(api::telegram::ChatId(
+				telegram_chat,
+			))),
+			(None, Some(sink)) if config.sinks.contains_key(sink) => {
+				Some(notifier::Chat::Sink(sink.clone()))
+			}
+			(None, Some(sink)) => {
+				problems.push(format!("{path}.sink: unknown sink \"{sink}\""));
+				None
+			}
+			_ => {
+				problems.push(format!("{path}: needs either a telegram-chat or a sink"));
+				None
+			}
+		};
+		if let Some(templates) = &tracker.templates {
+			if !config.templates.contains_key(templates) {
+				problems.push(format!(
+					"{path}.templates: unknown template set \"{templates}\""
+				));
+			}
+		}
+		let overridden = tracker.telegram_chat.is_some_and(|telegram_chat| {
+			config.telegram_chat_templates.contains_key(&telegram_chat)
+		});
+		if let (Some(chat), false) = (&chat, overridden) {
+			let name = |templates: &Option<String>| match templates {
+				Some(templates) => format!("template set \"{templates}\""),
+				None => String::from("default template set"),
+			};
+			match chats_templates.entry(chat.clone()) {
+				Entry::Vacant(entry) => {
+					entry.insert((&tracker.templates, path.clone()));
+				}
+				Entry::Occupied(entry) if *entry.get().0 != tracker.templates => {
+					let (templates, at) = entry.get();
+					problems.push(format!(
+						"{path}.templates: {} for the same chat as {at}, which uses the {}",
+						name(&tracker.templates),
+						name(templates)
+					));
+				}
+				Entry::Occupied(_) => {}
+			}
+		}
+
+		for (key, platforms_names, players) in [
+			(
+				"riot-lol-players",
+				&tracker.riot_lol_platforms_names,
+				&mut lol_players,
+			),
+			(
+				"riot-tft-players",
+				&tracker.riot_tft_platforms_names,
+				&mut tft_players,
+			),
+		] {
+			for (platform, names) in platforms_names {
+				let path = format!("{path}.{key}.{platform}");
+				let Ok(platform) = super::parse_platform(platform) else {
+					problems.push(format!("{path}: unknown Riot platform"));
+					continue;
+				};
+				for (index, name) in names.iter().enumerate() {
+					let path = format!("{path}[{index}]");
+					if name.trim().is_empty() {
+						problems.push(format!("{path}: empty player name"));
+						continue;
+					}
+					let Some(chat) = &chat else {
+						continue;
+					};
+					match players.entry((chat.clone(), platform, name.to_lowercase())) {
+						Entry::Vacant(entry) => {
+							entry.insert(path);
+						}
+						Entry::Occupied(entry) => problems.push(format!(
+							"{path}: duplicate player \"{name}\" in the same chat, also at {}",
+							entry.get()
+						)),
+					}
+				}
+			}
+		}
+	}
+
+	for (telegram_chat, templates) in &config.telegram_chat_templates {
+		let path = format!("$.telegram-chat-templates.{telegram_chat}");
+		if *telegram_chat == 0 {
+			problems.push(format!("{path}: invalid Telegram chat id 0"));
+		}
+		if !config.templates.contains_key(templates) {
+			problems.push(format!("{path}: unknown template set \"{templates}\""));
+		}
+	}
+
+	for (key, polling) in [
+		("riot-lol-polling", &config.riot_lol_polling),
+		("riot-tft-polling", &config.riot_tft_polling),
+	] {
+		let path = format!("$.{key}");
+		if !(polling.idle_factor.is_finite() && polling.idle_factor >= 0.0) {
+			problems.push(format!(
+				"{path}.idle-factor: expected a positive number or 0, got {}",
+				polling.idle_factor
+			));
+		}
+		if polling.max_interval < polling.min_interval {
+			problems.push(format!(
+				"{path}.max-interval: shorter than min-interval ({})",
+				polling.min_interval
+			));
+		}
+		if polling.max_interval > MAX_POLLING_INTERVAL {
+			problems.push(format!(
+				"{path}.max-interval: longer than {MAX_POLLING_INTERVAL} seconds (a week)"
+			));
+		}
+	}
+
+	// Default template set, then the named ones
+	let sets = [(
+		String::from("$"),
+		&config.riot_lol_message,
+		&config.riot_tft_message,
+	)]
+	.into_iter()
+	.chain(config.templates.iter().map(|(name, templates)| {
+		(
+			format!("$.templates.{name}"),
+			&templates.riot_lol_message,
+			&templates.riot_tft_message,
+		)
+	}));
+	for (prefix, lol_message, tft_message) in sets {
+		for (path, problem) in message::riot::lol::validate(lol_message) {
+			problems.push(format!("{prefix}.riot-lol-message.{path}: {problem}"));
+		}
+		for (path, problem) in message::riot::tft::validate(tft_message) {
+			problems.push(format!("{prefix}.riot-tft-message.{path}: {problem}"));
+		}
+	}
+
+	match problems.is_empty() {
+		true => Ok(()),
+		false => Err(problems),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn example() -> config::Config {
+		serde_json::from_str(&std::fs::read_to_string("config.json").unwrap()).unwrap()
+	}
+
+	#[test]
+	fn problems() {
+		assert_eq!(validate(&example()), Ok(()));
+
+		let mut config = example();
+		let chats: config::Config = serde_json::from_value(serde_json::json!({
+			"riot-lol-message": { "single": {}, "multiple": {} },
+			"riot-tft-message": { "single": {}, "duo": {} },
+			"telegram-chat-templates": { "0": "spanish", "42": "french" },
+			"trackers": [
+				{ "telegram-chat": 0 },
+				{ "telegram-chat": 1, "sink": "team" },
+				{
+					"telegram-chat": 42,
+					"templates": "french",
+					"riot-lol-players": { "EUW": ["Faker#KR1"], "XX": ["Caps"] }
+				},
+				{ "telegram-chat": 42, "riot-lol-players": { "euw": ["faker#kr1"] } },
+				{ "sink": "team" },
+				{ "telegram-chat": 7 },
+				{ "telegram-chat": 7, "templates": "spanish" },
+			],
+		}))
+		.unwrap();
+		config.telegram_chat_templates = chats.telegram_chat_templates;
+		config.trackers = chats.trackers;
+		config.riot_lol_message.single.win_single =
+			String::from("<b>{summoner_name}</b> {sumoner_name} {sumoner_nam}");
+		config.riot_tft_message.duo.top_duo = String::default();
+		config.riot_tft_message.duo.top_single = String::from("{champion}");
+		config.riot_lol_polling.idle_factor = f64::NAN;
+		config.riot_lol_polling.max_interval = u64::MAX;
+		config.riot_tft_polling.min_interval = 7200;
+
+		assert_eq!(
+			validate(&config),
+			Err(vec![
+				String::from("$.trackers[0].telegram-chat: invalid Telegram chat id 0"),
+				String::from("$.trackers[1]: needs either a telegram-chat or a sink"),
+				String::from("$.trackers[2].templates: unknown template set \"french\""),
+				String::from("$.trackers[2].riot-lol-players.XX: unknown Riot platform"),
+				String::from(
+					"$.trackers[3].riot-lol-players.euw[0]: duplicate player \"faker#kr1\" in the same chat, also at $.trackers[2].riot-lol-players.EUW[0]"
+				),
+				String::from("$.trackers[4].sink: unknown sink \"team\""),
+				String::from(
+					"$.trackers[6].templates: template set \"spanish\" for the same chat as $.trackers[5], which uses the default template set"
+				),
+				String::from("$.telegram-chat-templates.0: invalid Telegram chat id 0"),
+				String::from("$.telegram-chat-templates.42: unknown template set \"french\""),
+				String::from(
+					"$.riot-lol-polling.idle-factor: expected a positive number or 0, got NaN"
+				),
+				String::from(
+					"$.riot-lol-polling.max-interval: longer than 604800 seconds (a week)"
+				),
+				String::from("$.riot-tft-polling.max-interval: shorter than min-interval (7200)"),
+				String::from(
+					"$.riot-lol-message.single.win-single: unknown placeholder {sumoner_nam}, did you mean {summoner_name}?"
+				),
+				String::from("$.riot-tft-message.duo.top-duo: empty template"),
+				String::from(
+					"$.riot-tft-message.duo.top-single: unknown placeholder {champion}, expected one of {mode} {top} {game_duration_min} {region} {summoner_name} {tier} {rank} {lp} {streak}"
+				),
+			])
+		);
+	}
+}
@@ -1,25 +1,335 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
+pub mod file;
 pub mod riot;
 
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case", deny_unknown_fields)]
 pub struct Config {
-	pub telegram_api_key: String,
-	pub riot_lol_api_key: String,
-	pub riot_tft_api_key: String,
+	#[serde(default = "default_database")]
+	pub database: String,
+	// Keep the compressed Riot payload of every stored game, so it can be re-rendered
+	#[serde(default)]
+	pub store_game_payloads: bool,
+	// Send a scoreboard (LOL) or standings (TFT) card with a short caption
+	// instead of the text messages
+	#[serde(default)]
+	pub image_messages: bool,
+	// API keys can be left out when given by environment (see `resolve_secrets`)
+	#[serde(default)]
+	pub telegram_api_key: Secret,
+	#[serde(default)]
+	pub riot_lol_api_key: Secret,
+	#[serde(default = "default_riot_rate_limit")]
+	pub riot_lol_rate_limit: String,
+	#[serde(default)]
+	pub riot_tft_api_key: Secret,
+	#[serde(default = "default_riot_rate_limit")]
+	pub riot_tft_rate_limit: String,
+	// Default template set
 	pub riot_lol_message: riot::lol::Message,
 	pub riot_tft_message: riot::tft::Message,
+	#[serde(default)]
+	pub riot_locale: riot::Locale,
+	// Named template sets, e.g. one per language
+	#[serde(default)]
+	pub templates: BTreeMap<String, Templates>,
+	// Template set by Telegram chat, overriding the one of its trackers
+	#[serde(default)]
+	pub telegram_chat_templates: BTreeMap<i64, String>,
+	// Discord, Slack and Matrix destinations by name, targeted by trackers
+	#[serde(default)]
+	pub sinks: BTreeMap<String, Sink>,
+	// JSON event of every stored game, e.g. for dashboards
+	#[serde(default)]
+	pub events: Events,
+	// Read-only HTTP API over the stored data, disabled if missing
+	#[serde(default)]
+	pub rest_api: Option<RestApi>,
+	// Prometheus metrics and health check, disabled if missing
+	#[serde(default)]
+	pub metrics: Option<Metrics>,
+	#[serde(default)]
+	pub riot_lol_polling: riot::Polling,
+	#[serde(default)]
+	pub riot_tft_polling: riot::Polling,
 	pub trackers: Vec<Tracker>,
 }
 
+impl Config {
+	// TELEGRAM_API_KEY, RIOT_LOL_API_KEY and RIOT_TFT_API_KEY override the API
+	// keys of the file. The Telegram one is only required with Telegram chats.
+	pub fn resolve_secrets(&mut self) -> Result<(), String> {
+		let telegram = self.uses_telegram();
+		for (secret, name, variable, required) in [
+			(
+				&mut self.telegram_api_key,
+				"telegram-api-key",
+				"TELEGRAM_API_KEY",
+				telegram,
+			),
+			(
+				&mut self.riot_lol_api_key,
+				"riot-lol-api-key",
+				"RIOT_LOL_API_KEY",
+				true,
+			),
+			(
+				&mut self.riot_tft_api_key,
+				"riot-tft-api-key",
+				"RIOT_TFT_API_KEY",
+				true,
+			),
+		] {
+			if let Some(value) = std::env::var(variable)
+				.ok()
+				.filter(|value| !value.is_empty())
+			{
+				*secret = Secret(value);
+			}
+			if required && secret.0.is_empty() {
+				return Err(format!(
+					"Missing secret {name}: set it in the configuration or in {variable}"
+				));
+			}
+		}
+		Ok(())
+	}
+
+	// Whether some tracker notifies a Telegram chat, which also enables the bot
+	// commands
+	pub fn uses_telegram(&self) -> bool {
+		self.trackers
+			.iter()
+			.any(|tracker| tracker.telegram_chat.is_some())
+	}
+}
+
+// SQLite file path or database URL
+fn default_database() -> String {
+	String::from("riot.sqlite")
+}
+
+// Application rate limits of a development or personal key
+fn default_riot_rate_limit() -> String {
+	String::from("20/1s,100/120s")
+}
+
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Templates {
+	pub riot_lol_message: riot::lol::Message,
+	pub riot_tft_message: riot::tft::Message,
+	#[serde(default)]
+	pub riot_locale: riot::Locale,
+}
+
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case", deny_unknown_fields)]
 pub struct Tracker {
-	#[serde(rename = "telegram-chat")]
-	pub telegram_chat: i64,
+	// Either a Telegram chat or a named sink
+	#[serde(default, rename = "telegram-chat")]
+	pub telegram_chat: Option<i64>,
+	#[serde(default)]
+	pub sink: Option<String>,
+	// Named template set, the default one if missing
+	#[serde(default)]
+	pub templates: Option<String>,
 	#[serde(default, rename = "riot-lol-players")]
-	pub riot_lol_platforms_names: std::collections::BTreeMap<String, Vec<String>>,
+	pub riot_lol_platforms_names: BTreeMap<String, Vec<String>>,
 	#[serde(default, rename = "riot-tft-players")]
-	pub riot_tft_platforms_names: std::collections::BTreeMap<String, Vec<String>>,
+	pub riot_tft_platforms_names: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(
+	tag = "type",
+	rename_all = "kebab-case",
+	rename_all_fields = "kebab-case",
+	deny_unknown_fields
+)]
+pub enum Sink {
+	Discord {
+		webhook_url: Secret,
+	},
+	// Incoming webhooks cannot upload images, only their captions are sent
+	Slack {
+		webhook_url: Secret,
+	},
+	Matrix {
+		homeserver: String,
+		access_token: Secret,
+		room_id: String,
+	},
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields, default)]
+pub struct Events {
+	// NDJSON file the events are appended to
+	pub file: Option<String>,
+	pub webhooks: Vec<Webhook>,
+	// Attempts after a failed delivery, waiting 1, 2, 4... seconds
+	pub retries: u32,
+}
+
+impl Default for Events {
+	fn default() -> Self {
+		Self {
+			file: None,
+			webhooks: Vec::default(),
+			retries: 3,
+		}
+	}
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Webhook {
+	pub url: String,
+	// Key of the HMAC-SHA256 signature of the body, sent as `X-Signature-256`
+	#[serde(default)]
+	pub secret: Option<Secret>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct RestApi {
+	// Socket address, e.g. "127.0.0.1:8080"
+	pub listen: String,
+	// Expected as `Authorization: Bearer <token>`
+	pub token: Secret,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Metrics {
+	// Socket address of `/metrics` and `/healthz`, e.g. "127.0.0.1:9090"
+	pub listen: String,
+}
+
+// Written as is, or as `env:VARIABLE` or `file:/path` to be read when loading.
+// Never printed, neither by `Debug` nor by `Serialize`.
+#[derive(Default, Clone, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+	pub fn expose(&self) -> &str {
+		&self.0
+	}
+
+	fn resolve(value: String) -> Result<Self, String> {
+		let secret = if let Some(variable) = value.strip_prefix("env:") {
+			std::env::var(variable).map_err(|_| {
+				format!("Missing secret: environment variable {variable} is not set")
+			})?
+		} else if let Some(path) = value.strip_prefix("file:") {
+			// Secret files usually end with a line break
+			std::fs::read_to_string(path)
+				.map(|secret| String::from(secret.trim_end()))
+				.map_err(|err| format!("Missing secret: error reading {path}: {err}"))?
+		} else {
+			value
+		};
+		match secret.is_empty() {
+			true => Err(String::from("Missing secret: empty value")),
+			false => Ok(Secret(secret)),
+		}
+	}
+}
+
+impl std::fmt::Debug for Secret {
+	fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		formatter.write_str("Secret(..)")
+	}
+}
+
+impl Serialize for Secret {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str("..")
+	}
+}
+
+impl<'de> Deserialize<'de> for Secret {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		Secret::resolve(String::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// Environment variable removed at the end of the test
+	struct Variable(&'static str);
+
+	impl Variable {
+		fn set(name: &'static str, value: &str) -> Self {
+			std::env::set_var(name, value);
+			Variable(name)
+		}
+	}
+
+	impl Drop for Variable {
+		fn drop(&mut self) {
+			std::env::remove_var(self.0);
+		}
+	}
+
+	#[test]
+	fn secrets() {
+		let path = std::env::temp_dir().join(format!(
+			"telegram-riot-secret-{}-{}",
+			std::process::id(),
+			chrono::Utc::now().timestamp_nanos()
+		));
+		std::fs::write(&path, "FROM-FILE\n").unwrap();
+		let _variable = Variable::set("TELEGRAM_RIOT_TEST_SECRET", "FROM-ENV");
+		let secrets: Vec<Secret> = serde_json::from_str(&format!(
+			r#"["PLAIN", "env:TELEGRAM_RIOT_TEST_SECRET", "file:{}"]"#,
+			path.display()
+		))
+		.unwrap();
+		assert_eq!(
+			secrets.iter().map(Secret::expose).collect::<Vec<_>>(),
+			["PLAIN", "FROM-ENV", "FROM-FILE"]
+		);
+		assert_eq!(format!("{:?}", secrets[0]), "Secret(..)");
+
+		let err = serde_json::from_str::<Secret>(r#""env:TELEGRAM_RIOT_TEST_MISSING""#)
+			.unwrap_err()
+			.to_string();
+		assert!(err.starts_with(
+			"Missing secret: environment variable TELEGRAM_RIOT_TEST_MISSING is not set"
+		));
+		std::fs::remove_file(path).unwrap();
+	}
+
+	#[test]
+	fn resolve_secrets() {
+		// Sinks only
+		let mut config = Config {
+			riot_lol_api_key: Secret(String::from("LOL")),
+			riot_tft_api_key: Secret(String::from("TFT")),
+			trackers: vec![Tracker {
+				sink: Some(String::from("discord")),
+				..Default::default()
+			}],
+			..Default::default()
+		};
+		config.resolve_secrets().unwrap();
+
+		config.trackers.push(Tracker {
+			telegram_chat: Some(42),
+			..Default::default()
+		});
+		assert_eq!(
+			config.resolve_secrets().unwrap_err(),
+			"Missing secret telegram-api-key: set it in the configuration or in TELEGRAM_API_KEY"
+		);
+		config.telegram_api_key = Secret(String::from("TELEGRAM"));
+		config.resolve_secrets().unwrap();
+	}
 }
@@ -0,0 +1,153 @@
+use std::path::{Path, PathBuf};
+
+use futures::{future::LocalBoxFuture, FutureExt};
+use serde_json::Value;
+
+// Configuration file in JSON, TOML (`.toml`) or YAML (`.yaml`, `.yml`), merged
+// with the files listed in its `include`, relative to it. Objects are merged and
+// arrays appended in order, values of the including file win over included ones.
+pub async fn read(path: &Path) -> Result<Value, String> {
+	read_including(path.to_path_buf(), Vec::default()).await
+}
+
+fn read_including(
+	path: PathBuf,
+	mut including: Vec<PathBuf>,
+) -> LocalBoxFuture<'static, Result<Value, String>> {
+	async move {
+		let text = tokio::fs::read_to_string(&path)
+			.await
+			.map_err(|err| format!("Error reading {}: {err}", path.display()))?;
+		let mut value = parse(&path, &text)
+			.map_err(|err| format!("Error parsing {}: {err}", path.display()))?;
+
+		let includes = match value
+			.as_object_mut()
+			.and_then(|object| object.remove("include"))
+		{
+			None => Vec::default(),
+			Some(Value::String(include)) => vec![include],
+			Some(Value::Array(includes)) => includes
+				.into_iter()
+				.map(|include| match include {
+					Value::String(include) => Ok(include),
+					_ => Err(format!("Invalid include in {}", path.display())),
+				})
+				.collect::<Result<_, _>>()?,
+			Some(_) => return Err(format!("Invalid include in {}", path.display())),
+		};
+
+		let canonical = tokio::fs::canonicalize(&path)
+			.await
+			.map_err(|err| format!("Error reading {}: {err}", path.display()))?;
+		if including.contains(&canonical) {
+			return Err(format!("Include cycle at {}", path.display()));
+		}
+		including.push(canonical);
+
+		let mut merged = Value::Object(serde_json::Map::default());
+		for include in includes {
+			let include = path.parent().unwrap_or(Path::new("")).join(include);
+			merge(
+				&mut merged,
+				read_including(include, including.clone()).await?,
+			);
+		}
+		merge(&mut merged, value);
+		Ok(merged)
+	}
+	.boxed_local()
+}
+
+fn parse(path: &Path, text: &str) -> Result<Value, String> {
+	match path.extension().and_then(|extension| extension.to_str()) {
+		Some("toml") => toml::from_str(text).map_err(|err| err.to_string()),
+		// Through YAML values, whose keys may be numbers (e.g. chat ids)
+		Some("yaml" | "yml") => serde_yaml::from_str::<serde_yaml::Value>(text)
+			.map_err(|err| err.to_string())
+			.and_then(|value| serde_json::to_value(value).map_err(|err| err.to_string())),
+		_ => serde_json::from_str(text).map_err(|err| err.to_string()),
+	}
+}
+
+fn merge(base: &mut Value, value: Value) {
+	match (base, value) {
+		(Value::Object(base), Value::Object(object)) => {
+			for (key, value) in object {
+				match base.get_mut(&key) {
+					Some(base) => merge(base, value),
+					None => {
+						base.insert(key, value);
+					}
+				}
+			}
+		}
+		(Value::Array(base), Value::Array(array)) => base.extend(array),
+		(base, value) => *base = value,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[tokio::test]
+	async fn includes() {
+		let directory = std::env::temp_dir().join(format!(
+			"telegram-riot-config-{}-{}",
+			std::process::id(),
+			chrono::Utc::now().timestamp_nanos()
+		));
+		std::fs::create_dir_all(directory.join("trackers")).unwrap();
+		std::fs::write(
+			directory.join("config.toml"),
+			r#"include = ["templates.yaml", "trackers/friends.json"]
+telegram-api-key = "KEY"
+trackers = [{ telegram-chat = 1 }]
+
+[riot-lol-message.single]
+win-single = """
+<b>{summoner_name}</b>
+{mode}"""
+"#,
+		)
+		.unwrap();
+		std::fs::write(
+			directory.join("templates.yaml"),
+			"riot-lol-message:\n  single:\n    win-single: Overridden\n    loss-single: Defeat\n  multiple:\n    win-multiple: Victory\nriot-tft-message:\n  single:\n    top-single: Top {top}\n  duo: {}\ntelegram-chat-templates:\n  42: spanish\n",
+		)
+		.unwrap();
+		std::fs::write(
+			directory.join("trackers/friends.json"),
+			r#"{ "trackers": [{ "telegram-chat": 2 }] }"#,
+		)
+		.unwrap();
+
+		let config: super::super::Config =
+			serde_json::from_value(read(&directory.join("config.toml")).await.unwrap()).unwrap();
+		assert_eq!(config.telegram_api_key.expose(), "KEY");
+		assert_eq!(
+			config.riot_lol_message.single.win_single,
+			"<b>{summoner_name}</b>\n{mode}"
+		);
+		assert_eq!(config.riot_lol_message.single.loss_single, "Defeat");
+		assert_eq!(config.riot_lol_message.multiple.win_multiple, "Victory");
+		assert_eq!(config.riot_tft_message.single.top_single, "Top {top}");
+		assert_eq!(config.telegram_chat_templates[&42], "spanish");
+		assert_eq!(
+			config
+				.trackers
+				.iter()
+				.map(|tracker| tracker.telegram_chat)
+				.collect::<Vec<_>>(),
+			[Some(2), Some(1)]
+		);
+
+		std::fs::write(directory.join("templates.yaml"), "include: config.toml\n").unwrap();
+		assert!(read(&directory.join("config.toml"))
+			.await
+			.unwrap_err()
+			.starts_with("Include cycle"));
+		std::fs::remove_dir_all(directory).unwrap();
+	}
+}
@@ -1,4 +1,48 @@
+use std::collections::BTreeMap;
+
 use super::{Deserialize, Serialize};
 
 pub mod lol;
 pub mod tft;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields, default)]
+pub struct Polling {
+	// Seconds between polls of a player who has just played
+	pub min_interval: u64,
+	// Seconds between polls of a dormant player
+	pub max_interval: u64,
+	// Seconds of poll interval added per second since the last game
+	pub idle_factor: f64,
+	// Check spectator status to detect players who are currently playing
+	pub spectator: bool,
+}
+
+impl Default for Polling {
+	fn default() -> Self {
+		Self {
+			min_interval: 20,
+			max_interval: 3600,
+			idle_factor: 0.01,
+			spectator: false,
+		}
+	}
+}
+
+// Names used in messages instead of the English ones
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields, default)]
+pub struct Locale {
+	// By queue identifier
+	pub queues: BTreeMap<u16, String>,
+	// By game mode, e.g. "ARAM"
+	pub modes: BTreeMap<String, String>,
+	// By tier, e.g. "GOLD"
+	pub tiers: BTreeMap<String, String>,
+	// By division, e.g. "IV"
+	pub divisions: BTreeMap<String, String>,
+	// By region, e.g. "EUW"
+	pub regions: BTreeMap<String, String>,
+	// By English text of image cards and achievements, e.g. "VICTORY"
+	pub labels: BTreeMap<String, String>,
+}
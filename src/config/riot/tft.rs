@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use super::{Deserialize, Serialize};
 
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
@@ -5,6 +7,12 @@ use super::{Deserialize, Serialize};
 pub struct Message {
 	pub single: MessageTemplate,
 	pub duo: MessageTemplate,
+	// Extra messages sent when a streak reaches a number of games
+	#[serde(default)]
+	pub streak: StreakTemplate,
+	// Sent once per player and achievement, none if empty
+	#[serde(default)]
+	pub achievement: String,
 }
 
 #[derive(Default, Clone, Debug, Serialize, Deserialize)]
@@ -17,3 +25,11 @@ pub struct MessageTemplate {
 	pub bottom_single_ranked: String,
 	pub bottom_duo: String,
 }
+
+// Templates by streak length
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields, default)]
+pub struct StreakTemplate {
+	pub top: BTreeMap<u32, String>,
+	pub bottom: BTreeMap<u32, String>,
+}
@@ -1,5 +1,31 @@
-pub use sqlx::{sqlite::SqlitePool, Result};
+pub use sqlx::{
+	any::{AnyKind as Kind, AnyPool as Pool},
+	Result,
+};
 
 use crate::api;
 
+pub mod migration;
 pub mod riot;
+
+pub use migration::migrate;
+
+// SQLite file paths are accepted as well as connection URLs
+// (e.g. "riot.sqlite", "sqlite://riot.sqlite", "postgres://user@localhost/riot")
+pub async fn connect(url: &str) -> Result<Pool> {
+	if url.contains("://") || url.starts_with("sqlite:") {
+		Pool::connect(url).await
+	} else {
+		Pool::connect(&format!("sqlite://{url}?mode=rwc")).await
+	}
+}
+
+// Placeholders of a multiple row VALUES clause: ($1, $2), ($3, $4)...
+fn values(rows: usize, columns: usize) -> String {
+	Vec::from_iter((0..rows).map(|row| {
+		let placeholders =
+			Vec::from_iter((1..=columns).map(|column| format!("${}", row * columns + column)));
+		format!("({})", placeholders.join(", "))
+	}))
+	.join(", ")
+}
@@ -0,0 +1,120 @@
+use sqlx::Executor;
+
+use super::{Kind, Pool, Result};
+
+// Applied in order, never edit a released migration: add a new one instead
+const MIGRATIONS: [(i64, &str, &str, &str); 5] = [
+	(
+		1,
+		"initial",
+		include_str!("migration/sqlite/0001-initial.sql"),
+		include_str!("migration/postgres/0001-initial.sql"),
+	),
+	(
+		2,
+		"player-cursors",
+		include_str!("migration/sqlite/0002-player-cursors.sql"),
+		include_str!("migration/postgres/0002-player-cursors.sql"),
+	),
+	(
+		3,
+		"game-payloads",
+		include_str!("migration/sqlite/0003-game-payloads.sql"),
+		include_str!("migration/postgres/0003-game-payloads.sql"),
+	),
+	(
+		4,
+		"game-result-performance",
+		include_str!("migration/sqlite/0004-game-result-performance.sql"),
+		include_str!("migration/postgres/0004-game-result-performance.sql"),
+	),
+	(
+		5,
+		"achievements",
+		include_str!("migration/sqlite/0005-achievements.sql"),
+		include_str!("migration/postgres/0005-achievements.sql"),
+	),
+];
+
+pub fn latest_version() -> i64 {
+	MIGRATIONS.last().map_or(0, |(version, ..)| *version)
+}
+
+pub async fn get_version(pool: &Pool) -> Result<i64> {
+	pool.execute(match pool.any_kind() {
+		Kind::Postgres => "CREATE TABLE IF NOT EXISTS SchemaVersion (Version BIGINT, Name VARCHAR(64), Date CHAR(19), PRIMARY KEY (Version))",
+		_ => "CREATE TABLE IF NOT EXISTS SchemaVersion (Version INTEGER, Name VARCHAR(64), Date DATETIME, PRIMARY KEY (Version))",
+	})
+	.await
+	.inspect_err(|err| {
+		tracing::error!(error = err.to_string(), "Error creating DB table (SchemaVersion)");
+	})?;
+
+	sqlx::query_scalar("SELECT COALESCE(MAX(Version), 0) FROM SchemaVersion")
+		.fetch_one(pool)
+		.await
+		.inspect_err(|err| {
+			tracing::error!(error = err.to_string(), "Error reading DB (SchemaVersion)");
+		})
+}
+
+// Versions and names of the migrations applied
+pub async fn migrate(pool: &Pool) -> Result<Vec<(i64, &'static str)>> {
+	let version = get_version(pool).await?;
+	if version > latest_version() {
+		tracing::error!(
+			version,
+			latest = latest_version(),
+			"DB schema is newer than this version supports"
+		);
+		return Err(sqlx::Error::Configuration(
+			format!(
+				"DB schema version {version} is newer than the latest known version {}",
+				latest_version()
+			)
+			.into(),
+		));
+	}
+
+	let mut transaction = pool.begin().await?;
+	let mut applied = Vec::default();
+
+	for (version, name, sqlite, postgres) in MIGRATIONS
+		.into_iter()
+		.filter(|(migration, ..)| *migration > version)
+	{
+		let sql = match pool.any_kind() {
+			Kind::Postgres => postgres,
+			_ => sqlite,
+		};
+		transaction.execute(sql).await.inspect_err(|err| {
+			tracing::error!(
+				version,
+				name,
+				error = err.to_string(),
+				"Error applying DB migration"
+			);
+		})?;
+
+		sqlx::query("INSERT INTO SchemaVersion (Version, Name, Date) VALUES($1, $2, $3)")
+			.bind(version)
+			.bind(name)
+			.bind(chrono::Utc::now().format("%Y-%m-%dT%H:%M:%S").to_string())
+			.execute(&mut transaction)
+			.await
+			.inspect_err(|err| {
+				tracing::error!(
+					version,
+					name,
+					error = err.to_string(),
+					"Error writing DB (SchemaVersion)"
+				);
+			})?;
+
+		tracing::info!(version, name, "Success applying DB migration");
+		applied.push((version, name));
+	}
+
+	transaction.commit().await?;
+	Ok(applied)
+}
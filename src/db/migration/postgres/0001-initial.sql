@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS RiotLolPlayers (Id VARCHAR(78), Name VARCHAR(32), PRIMARY KEY (Id));
+CREATE TABLE IF NOT EXISTS RiotLolGames (Id VARCHAR(32), Platform VARCHAR(4), Queue INTEGER, Date CHAR(19), PRIMARY KEY (Id, Platform));
+CREATE TABLE IF NOT EXISTS RiotLolGameResults (GameId VARCHAR(32), Platform VARCHAR(4), PlayerId VARCHAR(78), Result INTEGER, LeagueTier INTEGER, LeagueRank INTEGER, LeaguePoints INTEGER, FOREIGN KEY (GameId, Platform) REFERENCES RiotLolGames(Id, Platform) ON UPDATE CASCADE ON DELETE RESTRICT, FOREIGN KEY (PlayerId) REFERENCES RiotLolPlayers(Id) ON UPDATE CASCADE ON DELETE RESTRICT, PRIMARY KEY (GameId, Platform, PlayerId));
+CREATE TABLE IF NOT EXISTS RiotTftPlayers (Id VARCHAR(78), Name VARCHAR(32), PRIMARY KEY (Id));
+CREATE TABLE IF NOT EXISTS RiotTftGames (Id VARCHAR(32), Platform VARCHAR(4), Queue INTEGER, Date CHAR(19), PRIMARY KEY (Id, Platform));
+CREATE TABLE IF NOT EXISTS RiotTftGameResults (GameId VARCHAR(32), Platform VARCHAR(4), PlayerId VARCHAR(78), Result INTEGER, LeagueTier INTEGER, LeagueRank INTEGER, LeaguePoints INTEGER, FOREIGN KEY (GameId, Platform) REFERENCES RiotTftGames(Id, Platform) ON UPDATE CASCADE ON DELETE RESTRICT, FOREIGN KEY (PlayerId) REFERENCES RiotTftPlayers(Id) ON UPDATE CASCADE ON DELETE RESTRICT, PRIMARY KEY (GameId, Platform, PlayerId));
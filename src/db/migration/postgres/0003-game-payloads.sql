@@ -0,0 +1,2 @@
+CREATE TABLE IF NOT EXISTS RiotLolGamePayloads (GameId VARCHAR(32), Platform VARCHAR(4), Payload BYTEA, FOREIGN KEY (GameId, Platform) REFERENCES RiotLolGames(Id, Platform) ON UPDATE CASCADE ON DELETE CASCADE, PRIMARY KEY (GameId, Platform));
+CREATE TABLE IF NOT EXISTS RiotTftGamePayloads (GameId VARCHAR(32), Platform VARCHAR(4), Payload BYTEA, FOREIGN KEY (GameId, Platform) REFERENCES RiotTftGames(Id, Platform) ON UPDATE CASCADE ON DELETE CASCADE, PRIMARY KEY (GameId, Platform));
@@ -0,0 +1,4 @@
+ALTER TABLE RiotLolGameResults ADD COLUMN Champion VARCHAR(32);
+ALTER TABLE RiotLolGameResults ADD COLUMN Kills INTEGER;
+ALTER TABLE RiotLolGameResults ADD COLUMN Deaths INTEGER;
+ALTER TABLE RiotLolGameResults ADD COLUMN Assists INTEGER;
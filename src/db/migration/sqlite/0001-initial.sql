@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS RiotLolPlayers (Id CHAR(78), Name VARCHAR(32), PRIMARY KEY (Id));
+CREATE TABLE IF NOT EXISTS RiotLolGames (Id CHAR(15), Platform CHAR(4), Queue CHAR(15), Date DATETIME, PRIMARY KEY (Id, Platform));
+CREATE TABLE IF NOT EXISTS RiotLolGameResults (GameId CHAR(15), Platform CHAR(4), PlayerId CHAR(78), Result TINYINT, LeagueTier TINYINT, LeagueRank TINYINT, LeaguePoints TINYINT, FOREIGN KEY (GameId, Platform) REFERENCES RiotLolGames(Id, Platform) ON UPDATE CASCADE ON DELETE RESTRICT, FOREIGN KEY (PlayerId) REFERENCES RiotLolPlayers(Id) ON UPDATE CASCADE ON DELETE RESTRICT, PRIMARY KEY (GameId, Platform, PlayerId));
+CREATE TABLE IF NOT EXISTS RiotTftPlayers (Id CHAR(78), Name VARCHAR(32), PRIMARY KEY (Id));
+CREATE TABLE IF NOT EXISTS RiotTftGames (Id CHAR(15), Platform CHAR(4), Queue CHAR(15), Date DATETIME, PRIMARY KEY (Id, Platform));
+CREATE TABLE IF NOT EXISTS RiotTftGameResults (GameId CHAR(15), Platform CHAR(4), PlayerId CHAR(78), Result TINYINT, LeagueTier TINYINT, LeagueRank TINYINT, LeaguePoints TINYINT, FOREIGN KEY (GameId, Platform) REFERENCES RiotTftGames(Id, Platform) ON UPDATE CASCADE ON DELETE RESTRICT, FOREIGN KEY (PlayerId) REFERENCES RiotTftPlayers(Id) ON UPDATE CASCADE ON DELETE RESTRICT, PRIMARY KEY (GameId, Platform, PlayerId));
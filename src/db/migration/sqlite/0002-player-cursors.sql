@@ -0,0 +1,2 @@
+CREATE TABLE IF NOT EXISTS RiotLolPlayerCursors (PlayerId CHAR(78), Timestamp BIGINT, FOREIGN KEY (PlayerId) REFERENCES RiotLolPlayers(Id) ON UPDATE CASCADE ON DELETE RESTRICT, PRIMARY KEY (PlayerId));
+CREATE TABLE IF NOT EXISTS RiotTftPlayerCursors (PlayerId CHAR(78), Timestamp BIGINT, FOREIGN KEY (PlayerId) REFERENCES RiotTftPlayers(Id) ON UPDATE CASCADE ON DELETE RESTRICT, PRIMARY KEY (PlayerId));
@@ -0,0 +1,2 @@
+CREATE TABLE IF NOT EXISTS RiotLolGamePayloads (GameId CHAR(15), Platform CHAR(4), Payload BLOB, FOREIGN KEY (GameId, Platform) REFERENCES RiotLolGames(Id, Platform) ON UPDATE CASCADE ON DELETE CASCADE, PRIMARY KEY (GameId, Platform));
+CREATE TABLE IF NOT EXISTS RiotTftGamePayloads (GameId CHAR(15), Platform CHAR(4), Payload BLOB, FOREIGN KEY (GameId, Platform) REFERENCES RiotTftGames(Id, Platform) ON UPDATE CASCADE ON DELETE CASCADE, PRIMARY KEY (GameId, Platform));
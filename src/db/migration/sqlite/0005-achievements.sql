@@ -0,0 +1,2 @@
+CREATE TABLE IF NOT EXISTS RiotLolAchievements (PlayerId CHAR(78), Achievement VARCHAR(32), GameId CHAR(15), Platform CHAR(4), FOREIGN KEY (PlayerId) REFERENCES RiotLolPlayers(Id) ON UPDATE CASCADE ON DELETE RESTRICT, FOREIGN KEY (GameId, Platform) REFERENCES RiotLolGames(Id, Platform) ON UPDATE CASCADE ON DELETE CASCADE, PRIMARY KEY (PlayerId, Achievement));
+CREATE TABLE IF NOT EXISTS RiotTftAchievements (PlayerId CHAR(78), Achievement VARCHAR(32), GameId CHAR(15), Platform CHAR(4), FOREIGN KEY (PlayerId) REFERENCES RiotTftPlayers(Id) ON UPDATE CASCADE ON DELETE RESTRICT, FOREIGN KEY (GameId, Platform) REFERENCES RiotTftGames(Id, Platform) ON UPDATE CASCADE ON DELETE CASCADE, PRIMARY KEY (PlayerId, Achievement));
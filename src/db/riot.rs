@@ -1,74 +1,68 @@
 pub mod lol;
 pub mod tft;
 
+use std::collections::BTreeSet;
+
 use super::{
 	api::riot,
 	api::riot::{Platform, Rank, Tier},
-	Result, SqlitePool,
+	values, Pool, Result,
 };
 
-async fn create_tables(pool: &SqlitePool, product: &str) -> Result<()> {
-	let mut transaction = pool.begin().await?;
+type League = (Tier, Rank, i32);
+// Champion, kills, deaths and assists
+type Performance<'a> = (&'a str, i32, i32, i32);
 
-	sqlx::query(
-		&format!("CREATE TABLE IF NOT EXISTS Riot{product}Players (Id CHAR(78), Name VARCHAR(32), PRIMARY KEY (Id))"),
-	)
-	.execute(&mut transaction)
-	.await
-	.map_err(|err| {
-		tracing::error!(product, error = err.to_string(), "Error creating DB table (Players)");
-		err
-	})
-	.map(|result| {
-		tracing::trace!(product, "Success creating DB table (Players)");
-		result
-	})?;
-
-	sqlx::query(
-		&format!("CREATE TABLE IF NOT EXISTS Riot{product}Games (Id CHAR(15), Platform CHAR(4), Queue CHAR(15), Date DATETIME, PRIMARY KEY (Id, Platform))"),
-	)
-	.execute(&mut transaction)
-	.await
-	.map_err(|err| {
-		tracing::error!(product, error = err.to_string(), "Error creating DB table (Games)");
-		err
-	})
-	.map(|result| {
-		tracing::trace!(product, "Success creating DB table (Games)");
-		result
-	})?;
+// Stored result of a player in a game
+pub struct GameResult {
+	pub game_id: String,
+	pub queue: i32,
+	// Milliseconds
+	pub timestamp: i64,
+	// LOL: 1 for a win and 0 for a loss, TFT: placement
+	pub result: i32,
+	pub league: Option<League>,
+	pub champion: Option<String>,
+	pub kills_deaths_assists: Option<(i32, i32, i32)>,
+}
 
-	sqlx::query(
-		&format!("CREATE TABLE IF NOT EXISTS Riot{product}GameResults (GameId CHAR(15), Platform CHAR(4), PlayerId CHAR(78), Result TINYINT, LeagueTier TINYINT, LeagueRank TINYINT, LeaguePoints TINYINT, FOREIGN KEY (GameId, Platform) REFERENCES Riot{product}Games(Id, Platform) ON UPDATE CASCADE ON DELETE RESTRICT, FOREIGN KEY (PlayerId) REFERENCES Riot{product}Players(Id) ON UPDATE CASCADE ON DELETE RESTRICT, PRIMARY KEY (GameId, Platform, PlayerId))"),
-	)
-	.execute(&mut transaction)
-	.await
-	.map_err(|err| {
-		tracing::error!(product, error = err.to_string(), "Error creating DB table (GameResults)");
-		err
-	})
-	.map(|result| {
-		tracing::trace!(product, "Success creating DB table (GameResults)");
-		result
-	})?;
+// History of a player before a game, used by the achievement rules
+pub struct History {
+	// Stored games, including this one
+	pub games: i64,
+	// Last known league in the queue of the game
+	pub previous_league: Option<League>,
+}
 
-	transaction.commit().await
+async fn count(pool: &Pool, product: &str) -> Result<(i64, i64, i64)> {
+	let mut counts = Vec::default();
+	for table in ["Players", "Games", "GameResults"] {
+		counts.push(
+			sqlx::query_scalar(&format!("SELECT COUNT(*) FROM Riot{product}{table}"))
+				.fetch_one(pool)
+				.await
+				.inspect_err(|err| {
+					tracing::error!(product, table, error = err.to_string(), "Error reading DB");
+				})?,
+		);
+	}
+	Ok((counts[0], counts[1], counts[2]))
 }
 
 async fn contains_game(
-	pool: &SqlitePool,
+	pool: &Pool,
 	product: &str,
 	game_id: &str,
 	platform: Platform,
 ) -> Result<bool> {
 	sqlx::query_scalar(&format!(
-		"SELECT COUNT(*) FROM Riot{product}Games WHERE Id = ? AND Platform = ?"
+		"SELECT COUNT(*) FROM Riot{product}Games WHERE Id = $1 AND Platform = $2"
 	))
 	.bind(game_id)
 	.bind(platform.to_string())
 	.fetch_one(pool)
 	.await
-	.map_err(|err| {
+	.inspect_err(|err| {
 		tracing::error!(
 			product,
 			platform = platform.as_region_str(),
@@ -76,37 +70,171 @@ async fn contains_game(
 			error = err.to_string(),
 			"Error reading DB"
 		);
-		err
 	})
 	.map(|result: i64| result > 0)
 }
 
+async fn get_last_game_timestamp(
+	pool: &Pool,
+	product: &str,
+	player_id: &str,
+) -> Result<Option<i64>> {
+	sqlx::query_scalar(&format!(
+		"SELECT MAX(Games.Date) FROM Riot{product}GameResults AS Results INNER JOIN Riot{product}Games AS Games ON Games.Id = Results.GameId AND Games.Platform = Results.Platform WHERE Results.PlayerId = $1"
+	))
+	.bind(player_id)
+	.fetch_one(pool)
+	.await
+	.inspect_err(|err| {
+		tracing::error!(
+			product,
+			player_id,
+			error = err.to_string(),
+			"Error reading DB"
+		);
+	})
+	.map(|date: Option<String>| {
+		date.and_then(|date| {
+			chrono::naive::NaiveDateTime::parse_from_str(&date, "%Y-%m-%dT%H:%M:%S").ok()
+		})
+		.map(|datetime| datetime.timestamp_millis())
+	})
+}
+
+async fn get_cursor(pool: &Pool, product: &str, player_id: &str) -> Result<Option<i64>> {
+	sqlx::query_scalar(&format!(
+		"SELECT Timestamp FROM Riot{product}PlayerCursors WHERE PlayerId = $1"
+	))
+	.bind(player_id)
+	.fetch_optional(pool)
+	.await
+	.inspect_err(|err| {
+		tracing::error!(
+			product,
+			player_id,
+			error = err.to_string(),
+			"Error reading DB (PlayerCursors)"
+		);
+	})
+}
+
+async fn set_cursor(pool: &Pool, product: &str, player_id: &str, timestamp: i64) -> Result<()> {
+	sqlx::query(&format!(
+		"INSERT INTO Riot{product}PlayerCursors (PlayerId, Timestamp) VALUES($1, $2) ON CONFLICT (PlayerId) DO UPDATE SET Timestamp = excluded.Timestamp WHERE Riot{product}PlayerCursors.Timestamp < excluded.Timestamp"
+	))
+	.bind(player_id)
+	.bind(timestamp)
+	.execute(pool)
+	.await
+	.inspect_err(|err| {
+		tracing::error!(
+			product,
+			player_id,
+			timestamp,
+			error = err.to_string(),
+			"Error writing DB (PlayerCursors)"
+		);
+	})
+	.map(|_| ())
+}
+
+// Moves the cursor of a player past the games of a poll (newest first) that
+// are stored or given up on, up to the oldest one that is not. Those not stored
+// are returned, oldest first, so a game that failed to be stored is polled
+// again until it is given up on.
+async fn advance_cursor(
+	pool: &Pool,
+	product: &str,
+	player_id: &str,
+	platform: Platform,
+	game_ids: &[String],
+	given_up: &BTreeSet<String>,
+) -> Result<Vec<String>> {
+	// Collected, as a filter held across awaits would make polling tasks not Send
+	let game_ids: Vec<&String> = game_ids
+		.iter()
+		.rev()
+		.filter(|game_id| !given_up.contains(*game_id))
+		.collect();
+	let mut game_ids = game_ids.into_iter().peekable();
+	let mut last_stored = None;
+	while let Some(game_id) = game_ids.peek() {
+		let date: Option<String> = sqlx::query_scalar(&format!(
+			"SELECT Date FROM Riot{product}Games WHERE Id = $1 AND Platform = $2"
+		))
+		.bind(game_id.as_str())
+		.bind(platform.to_string())
+		.fetch_optional(pool)
+		.await
+		.inspect_err(|err| {
+			tracing::error!(
+				product,
+				platform = platform.as_region_str(),
+				game_id,
+				error = err.to_string(),
+				"Error reading DB (Games)"
+			);
+		})?;
+		let Some(date) = date else {
+			break;
+		};
+		last_stored = chrono::naive::NaiveDateTime::parse_from_str(&date, "%Y-%m-%dT%H:%M:%S")
+			.ok()
+			.or(last_stored);
+		game_ids.next();
+	}
+
+	// Games of a player do not overlap, so the next one starts after this one
+	if let Some(last_stored) = last_stored {
+		set_cursor(
+			pool,
+			product,
+			player_id,
+			last_stored.timestamp_millis() + 1000,
+		)
+		.await?;
+	}
+	Ok(game_ids.cloned().collect())
+}
+
+// Only LOL results have performance columns (champion, kills, deaths and assists)
+fn has_performance(product: &str) -> bool {
+	product == "Lol"
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn insert_game(
-	pool: &SqlitePool,
+	pool: &Pool,
 	product: &str,
 	game_id: &str,
 	platform: Platform,
 	queue: i32,
 	timestamp: i64,
-	player_ids_results_leagues: &[(&str, i32, Option<(Tier, Rank, i32)>)],
+	player_ids_results_leagues_performances: &[(
+		&str,
+		i32,
+		Option<League>,
+		Option<Performance<'_>>,
+	)],
+	payload: Option<&[u8]>,
 ) -> Result<()> {
 	let mut transaction = pool.begin().await?;
 
 	let platform_string = platform.to_string();
 
 	sqlx::query(&format!(
-		"INSERT INTO Riot{product}Games (Id, Platform, Queue, Date) VALUES(?, ?, ?, ?)"
+		"INSERT INTO Riot{product}Games (Id, Platform, Queue, Date) VALUES($1, $2, $3, $4)"
 	))
 	.bind(game_id)
 	.bind(&platform_string)
 	.bind(queue)
 	.bind(
 		chrono::naive::NaiveDateTime::from_timestamp_millis(timestamp)
-		.map(|datetime| datetime.format("%Y-%m-%dT%H:%M:%S").to_string()),
+			.map(|datetime| datetime.format("%Y-%m-%dT%H:%M:%S").to_string()),
 	)
 	.execute(&mut transaction)
 	.await
-	.map_err(|err| {
+	.inspect_err(|err| {
 		tracing::error!(
 			product,
 			platform = platform.as_region_str(),
@@ -114,9 +242,8 @@ async fn insert_game(
 			error = err.to_string(),
 			"Error writing DB (Games)"
 		);
-		err
 	})
-	.map(|result| {
+	.inspect(|result| {
 		tracing::trace!(
 			product,
 			platform = platform.as_region_str(),
@@ -124,57 +251,482 @@ async fn insert_game(
 			rows = result.rows_affected(),
 			"Success writing DB (Games)"
 		);
-		result
 	})?;
 
-	if !player_ids_results_leagues.is_empty() {
-		sqlx::query_builder::QueryBuilder::new(format!(
-			"INSERT INTO Riot{product}GameResults (GameId, Platform, PlayerId, Result, LeagueTier, LeagueRank, LeaguePoints) "
+	if let Some(payload) = payload {
+		sqlx::query(&format!(
+			"INSERT INTO Riot{product}GamePayloads (GameId, Platform, Payload) VALUES($1, $2, $3)"
 		))
-		.push_values(player_ids_results_leagues, |mut value, (player_id, result, league)| {
+		.bind(game_id)
+		.bind(&platform_string)
+		.bind(compress(payload))
+		.execute(&mut transaction)
+		.await
+		.inspect_err(|err| {
+			tracing::error!(
+				product,
+				platform = platform.as_region_str(),
+				game_id,
+				error = err.to_string(),
+				"Error writing DB (GamePayloads)"
+			);
+		})?;
+	}
+
+	if !player_ids_results_leagues_performances.is_empty() {
+		let sql = match has_performance(product) {
+			true => format!(
+				"INSERT INTO Riot{product}GameResults (GameId, Platform, PlayerId, Result, LeagueTier, LeagueRank, LeaguePoints, Champion, Kills, Deaths, Assists) VALUES {}",
+				values(player_ids_results_leagues_performances.len(), 11)
+			),
+			false => format!(
+				"INSERT INTO Riot{product}GameResults (GameId, Platform, PlayerId, Result, LeagueTier, LeagueRank, LeaguePoints) VALUES {}",
+				values(player_ids_results_leagues_performances.len(), 7)
+			),
+		};
+		let mut query = sqlx::query(&sql);
+		for (player_id, result, league, performance) in player_ids_results_leagues_performances {
 			let (tier, rank, points) = match league {
 				Some((tier, rank, points)) => (Some(*tier), Some(*rank), Some(*points)),
 				None => (None, None, None),
 			};
-			value
-				.push_bind(game_id)
-				.push_bind(&platform_string)
-				.push_bind(*player_id)
-				.push_bind(*result)
-				.push_bind(tier.map(u8::from))
-				.push_bind(rank.map(u8::from))
-				.push_bind(points);
+			let (champion, kills, deaths, assists) = match performance {
+				Some((champion, kills, deaths, assists)) => {
+					(Some(*champion), Some(*kills), Some(*deaths), Some(*assists))
+				}
+				None => (None, None, None, None),
+			};
+			query = query
+				.bind(game_id)
+				.bind(&platform_string)
+				.bind(*player_id)
+				.bind(*result)
+				.bind(tier.map(|tier| i32::from(u8::from(tier))))
+				.bind(rank.map(|rank| i32::from(u8::from(rank))))
+				.bind(points);
+			if has_performance(product) {
+				query = query.bind(champion).bind(kills).bind(deaths).bind(assists);
+			}
+		}
+		query
+			.execute(&mut transaction)
+			.await
+			.inspect_err(|err| {
+				tracing::error!(
+					product,
+					platform = platform.as_region_str(),
+					game_id,
+					error = err.to_string(),
+					"Error writing DB (GameResults)"
+				);
+			})
+			.inspect(|result| {
+				tracing::trace!(
+					product,
+					platform = platform.as_region_str(),
+					game_id,
+					rows = result.rows_affected(),
+					"Success writing DB (GameResults)"
+				);
+			})?;
+	}
+
+	transaction.commit().await
+}
+
+// Platform and uncompressed payload of a stored game
+async fn get_game_payload(
+	pool: &Pool,
+	product: &str,
+	game_id: &str,
+) -> Result<Option<(Platform, Vec<u8>)>> {
+	sqlx::query_as(&format!(
+		"SELECT Platform, Payload FROM Riot{product}GamePayloads WHERE GameId = $1"
+	))
+	.bind(game_id)
+	.fetch_optional(pool)
+	.await
+	.inspect_err(|err| {
+		tracing::error!(
+			product,
+			game_id,
+			error = err.to_string(),
+			"Error reading DB (GamePayloads)"
+		);
+	})?
+	.map(|(platform, payload): (String, Vec<u8>)| {
+		let platform = platform.parse().map_err(|_| {
+			sqlx::Error::Decode(format!("Unknown Riot platform \"{platform}\"").into())
+		})?;
+		let payload = decompress(&payload).map_err(|err| sqlx::Error::Decode(err.into()))?;
+		Ok((platform, payload))
+	})
+	.transpose()
+}
+
+// Id, name, result and league of every stored player of a game
+async fn get_game_results(
+	pool: &Pool,
+	product: &str,
+	game_id: &str,
+	platform: Platform,
+) -> Result<Vec<(String, String, i32, Option<League>)>> {
+	let sql = format!(
+		"SELECT Players.Id, Players.Name, Results.Result, Results.LeagueTier, Results.LeagueRank, Results.LeaguePoints FROM Riot{product}GameResults AS Results INNER JOIN Riot{product}Players AS Players ON Players.Id = Results.PlayerId WHERE Results.GameId = $1 AND Results.Platform = $2"
+	);
+	let rows =
+		sqlx::query_as::<_, (String, String, i32, Option<i32>, Option<i32>, Option<i32>)>(&sql)
+			.bind(game_id)
+			.bind(platform.to_string())
+			.fetch_all(pool)
+			.await
+			.inspect_err(|err| {
+				tracing::error!(
+					product,
+					platform = platform.as_region_str(),
+					game_id,
+					error = err.to_string(),
+					"Error reading DB (GameResults)"
+				);
+			})?;
+	Ok(rows
+		.into_iter()
+		.map(|(id, name, result, tier, rank, points)| {
+			(id, name, result, decode_league(tier, rank, points))
 		})
-		.build()
-		.execute(&mut transaction)
+		.collect())
+}
+
+// Id and name of every stored player
+async fn get_players(pool: &Pool, product: &str) -> Result<Vec<(String, String)>> {
+	sqlx::query_as(&format!(
+		"SELECT Id, Name FROM Riot{product}Players ORDER BY Name"
+	))
+	.fetch_all(pool)
+	.await
+	.inspect_err(|err| {
+		tracing::error!(
+			product,
+			error = err.to_string(),
+			"Error reading DB (Players)"
+		);
+	})
+}
+
+// Platform, queue and start (milliseconds) of a stored game
+async fn get_game_info(
+	pool: &Pool,
+	product: &str,
+	game_id: &str,
+) -> Result<Option<(Platform, i32, i64)>> {
+	let sql = format!(
+		"SELECT Platform, CAST(Queue AS INTEGER), Date FROM Riot{product}Games WHERE Id = $1"
+	);
+	sqlx::query_as::<_, (String, i32, String)>(&sql)
+		.bind(game_id)
+		.fetch_optional(pool)
 		.await
-		.map_err(|err| {
+		.inspect_err(|err| {
 			tracing::error!(
 				product,
-				platform = platform.as_region_str(),
 				game_id,
 				error = err.to_string(),
-				"Error writing DB (GameResults)"
+				"Error reading DB (Games)"
 			);
-			err
+		})?
+		.map(|(platform, queue, date)| {
+			let platform = platform.parse().map_err(|_| {
+				sqlx::Error::Decode(format!("Unknown Riot platform \"{platform}\"").into())
+			})?;
+			let timestamp =
+				chrono::naive::NaiveDateTime::parse_from_str(&date, "%Y-%m-%dT%H:%M:%S")
+					.map_or(0, |datetime| datetime.timestamp_millis());
+			Ok((platform, queue, timestamp))
 		})
-		.map(|result| {
-			tracing::trace!(
-				product,
-				platform = platform.as_region_str(),
+		.transpose()
+}
+
+// Id, name, queue and latest known league of every player in every queue they
+// played ranked
+async fn get_leagues(pool: &Pool, product: &str) -> Result<Vec<(String, String, i32, League)>> {
+	let sql = format!(
+		"SELECT Players.Id, Players.Name, CAST(Games.Queue AS INTEGER), Results.LeagueTier, Results.LeagueRank, Results.LeaguePoints FROM Riot{product}GameResults AS Results INNER JOIN Riot{product}Games AS Games ON Games.Id = Results.GameId AND Games.Platform = Results.Platform INNER JOIN Riot{product}Players AS Players ON Players.Id = Results.PlayerId INNER JOIN (SELECT Results.PlayerId, Games.Queue, MAX(Games.Date) AS Date FROM Riot{product}GameResults AS Results INNER JOIN Riot{product}Games AS Games ON Games.Id = Results.GameId AND Games.Platform = Results.Platform WHERE Results.LeagueTier IS NOT NULL GROUP BY Results.PlayerId, Games.Queue) AS Latest ON Latest.PlayerId = Results.PlayerId AND Latest.Queue = Games.Queue AND Latest.Date = Games.Date WHERE Results.LeagueTier IS NOT NULL ORDER BY Games.Date DESC"
+	);
+	let rows =
+		sqlx::query_as::<_, (String, String, i32, Option<i32>, Option<i32>, Option<i32>)>(&sql)
+			.fetch_all(pool)
+			.await
+			.inspect_err(|err| {
+				tracing::error!(
+					product,
+					error = err.to_string(),
+					"Error reading DB (GameResults)"
+				);
+			})?;
+	Ok(rows
+		.into_iter()
+		.filter_map(|(id, name, queue, tier, rank, points)| {
+			decode_league(tier, rank, points).map(|league| (id, name, queue, league))
+		})
+		.collect())
+}
+
+// Results of a player in the games started since `start_time` (milliseconds), oldest first
+async fn get_results(
+	pool: &Pool,
+	product: &str,
+	player_id: &str,
+	start_time: i64,
+) -> Result<Vec<GameResult>> {
+	let performance = match has_performance(product) {
+		true => "Results.Champion, Results.Kills, Results.Deaths, Results.Assists",
+		false => "CAST(NULL AS VARCHAR(32)), CAST(NULL AS INTEGER), CAST(NULL AS INTEGER), CAST(NULL AS INTEGER)",
+	};
+	let sql = format!(
+		"SELECT Games.Id, CAST(Games.Queue AS INTEGER), Games.Date, Results.Result, Results.LeagueTier, Results.LeagueRank, Results.LeaguePoints, {performance} FROM Riot{product}GameResults AS Results INNER JOIN Riot{product}Games AS Games ON Games.Id = Results.GameId AND Games.Platform = Results.Platform WHERE Results.PlayerId = $1 AND Games.Date >= $2 ORDER BY Games.Date"
+	);
+	let rows = sqlx::query_as::<
+		_,
+		(
+			String,
+			i32,
+			String,
+			i32,
+			Option<i32>,
+			Option<i32>,
+			Option<i32>,
+			Option<String>,
+			Option<i32>,
+			Option<i32>,
+			Option<i32>,
+		),
+	>(&sql)
+	.bind(player_id)
+	.bind(
+		chrono::naive::NaiveDateTime::from_timestamp_millis(start_time)
+			.map(|datetime| datetime.format("%Y-%m-%dT%H:%M:%S").to_string()),
+	)
+	.fetch_all(pool)
+	.await
+	.inspect_err(|err| {
+		tracing::error!(
+			product,
+			player_id,
+			error = err.to_string(),
+			"Error reading DB (GameResults)"
+		);
+	})?;
+	Ok(rows
+		.into_iter()
+		.map(
+			|(
 				game_id,
-				rows = result.rows_affected(),
-				"Success writing DB (GameResults)"
+				queue,
+				date,
+				result,
+				tier,
+				rank,
+				points,
+				champion,
+				kills,
+				deaths,
+				assists,
+			)| {
+				GameResult {
+					game_id,
+					queue,
+					timestamp: chrono::naive::NaiveDateTime::parse_from_str(
+						&date,
+						"%Y-%m-%dT%H:%M:%S",
+					)
+					.map_or(0, |datetime| datetime.timestamp_millis()),
+					result,
+					league: decode_league(tier, rank, points),
+					champion,
+					kills_deaths_assists: kills
+						.zip(deaths)
+						.zip(assists)
+						.map(|((kills, deaths), assists)| (kills, deaths, assists)),
+				}
+			},
+		)
+		.collect())
+}
+
+// Consecutive wins (positive) or losses (negative) of a player in a queue, up to
+// the game started at `timestamp` (milliseconds), counting at most 100 games
+async fn get_streak(
+	pool: &Pool,
+	product: &str,
+	player_id: &str,
+	queue: i32,
+	timestamp: i64,
+	won: fn(i32) -> bool,
+) -> Result<i32> {
+	let sql = format!(
+		"SELECT Results.Result FROM Riot{product}GameResults AS Results INNER JOIN Riot{product}Games AS Games ON Games.Id = Results.GameId AND Games.Platform = Results.Platform WHERE Results.PlayerId = $1 AND CAST(Games.Queue AS INTEGER) = $2 AND Games.Date <= $3 ORDER BY Games.Date DESC LIMIT 100"
+	);
+	let results: Vec<i32> = sqlx::query_scalar(&sql)
+		.bind(player_id)
+		.bind(queue)
+		.bind(
+			chrono::naive::NaiveDateTime::from_timestamp_millis(timestamp)
+				.map(|datetime| datetime.format("%Y-%m-%dT%H:%M:%S").to_string()),
+		)
+		.fetch_all(pool)
+		.await
+		.inspect_err(|err| {
+			tracing::error!(
+				product,
+				player_id,
+				error = err.to_string(),
+				"Error reading DB (GameResults)"
 			);
-			result
 		})?;
-	}
 
-	transaction.commit().await
+	let Some(last) = results.first().map(|result| won(*result)) else {
+		return Ok(0);
+	};
+	let games = results
+		.iter()
+		.take_while(|result| won(**result) == last)
+		.count() as i32;
+	Ok(if last { games } else { -games })
+}
+
+async fn get_history(
+	pool: &Pool,
+	product: &str,
+	player_id: &str,
+	queue: i32,
+	timestamp: i64,
+) -> Result<History> {
+	let games = sqlx::query_scalar(&format!(
+		"SELECT COUNT(*) FROM Riot{product}GameResults WHERE PlayerId = $1"
+	))
+	.bind(player_id)
+	.fetch_one(pool)
+	.await
+	.inspect_err(|err| {
+		tracing::error!(
+			product,
+			player_id,
+			error = err.to_string(),
+			"Error reading DB (GameResults)"
+		);
+	})?;
+
+	let sql = format!(
+		"SELECT Results.LeagueTier, Results.LeagueRank, Results.LeaguePoints FROM Riot{product}GameResults AS Results INNER JOIN Riot{product}Games AS Games ON Games.Id = Results.GameId AND Games.Platform = Results.Platform WHERE Results.PlayerId = $1 AND CAST(Games.Queue AS INTEGER) = $2 AND Games.Date < $3 AND Results.LeagueTier IS NOT NULL ORDER BY Games.Date DESC LIMIT 1"
+	);
+	let previous_league = sqlx::query_as::<_, (Option<i32>, Option<i32>, Option<i32>)>(&sql)
+		.bind(player_id)
+		.bind(queue)
+		.bind(
+			chrono::naive::NaiveDateTime::from_timestamp_millis(timestamp)
+				.map(|datetime| datetime.format("%Y-%m-%dT%H:%M:%S").to_string()),
+		)
+		.fetch_optional(pool)
+		.await
+		.inspect_err(|err| {
+			tracing::error!(
+				product,
+				player_id,
+				error = err.to_string(),
+				"Error reading DB (GameResults)"
+			);
+		})?
+		.and_then(|(tier, rank, points)| decode_league(tier, rank, points));
+
+	Ok(History {
+		games,
+		previous_league,
+	})
+}
+
+// Whether the achievement was not unlocked yet
+async fn insert_achievement(
+	pool: &Pool,
+	product: &str,
+	player_id: &str,
+	achievement: &str,
+	game_id: &str,
+	platform: Platform,
+) -> Result<bool> {
+	sqlx::query(&format!(
+		"INSERT INTO Riot{product}Achievements (PlayerId, Achievement, GameId, Platform) VALUES($1, $2, $3, $4) ON CONFLICT DO NOTHING"
+	))
+	.bind(player_id)
+	.bind(achievement)
+	.bind(game_id)
+	.bind(platform.to_string())
+	.execute(pool)
+	.await
+	.inspect_err(|err| {
+		tracing::error!(
+			product,
+			player_id,
+			achievement,
+			error = err.to_string(),
+			"Error writing DB (Achievements)"
+		);
+	})
+	.map(|result| result.rows_affected() > 0)
+}
+
+// Achievements of a player and the start of the game unlocking them
+// (milliseconds), oldest first
+async fn get_achievements(
+	pool: &Pool,
+	product: &str,
+	player_id: &str,
+) -> Result<Vec<(String, i64)>> {
+	let sql = format!(
+		"SELECT Achievements.Achievement, Games.Date FROM Riot{product}Achievements AS Achievements INNER JOIN Riot{product}Games AS Games ON Games.Id = Achievements.GameId AND Games.Platform = Achievements.Platform WHERE Achievements.PlayerId = $1 ORDER BY Games.Date, Achievements.Achievement"
+	);
+	let rows = sqlx::query_as::<_, (String, String)>(&sql)
+		.bind(player_id)
+		.fetch_all(pool)
+		.await
+		.inspect_err(|err| {
+			tracing::error!(
+				product,
+				player_id,
+				error = err.to_string(),
+				"Error reading DB (Achievements)"
+			);
+		})?;
+	Ok(rows
+		.into_iter()
+		.map(|(achievement, date)| {
+			let timestamp =
+				chrono::naive::NaiveDateTime::parse_from_str(&date, "%Y-%m-%dT%H:%M:%S")
+					.map_or(0, |datetime| datetime.timestamp_millis());
+			(achievement, timestamp)
+		})
+		.collect())
+}
+
+fn decode_league(tier: Option<i32>, rank: Option<i32>, points: Option<i32>) -> Option<League> {
+	let tier = Tier::try_from(u8::try_from(tier?).ok()?).ok()?;
+	let rank = Rank::try_from(u8::try_from(rank?).ok()?).ok()?;
+	Some((tier, rank, points?))
+}
+
+fn compress(payload: &[u8]) -> Vec<u8> {
+	let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+	std::io::Write::write_all(&mut encoder, payload).unwrap();
+	encoder.finish().unwrap()
+}
+
+fn decompress(payload: &[u8]) -> std::io::Result<Vec<u8>> {
+	let mut decoded = Vec::new();
+	std::io::Read::read_to_end(&mut flate2::read::GzDecoder::new(payload), &mut decoded)?;
+	Ok(decoded)
 }
 
 async fn insert_players(
-	pool: &SqlitePool,
+	pool: &Pool,
 	product: &str,
 	player_ids_names: &[(&str, &str)],
 ) -> Result<()> {
@@ -182,13 +734,13 @@ async fn insert_players(
 
 	for (id, name) in player_ids_names {
 		sqlx::query(&format!(
-			"UPDATE OR IGNORE Riot{product}Players SET Name=? WHERE Id=?"
+			"UPDATE Riot{product}Players SET Name = $1 WHERE Id = $2"
 		))
 		.bind(name)
 		.bind(id)
 		.execute(&mut transaction)
 		.await
-		.map_err(|err| {
+		.inspect_err(|err| {
 			tracing::error!(
 				product,
 				id,
@@ -196,37 +748,428 @@ async fn insert_players(
 				error = err.to_string(),
 				"Error writing DB (Players)"
 			);
-			err
 		})?;
 	}
 
 	if !player_ids_names.is_empty() {
-		sqlx::query_builder::QueryBuilder::new(format!(
-			"INSERT OR IGNORE INTO Riot{product}Players (Id, Name) "
-		))
-		.push_values(player_ids_names, |mut value, (id, name)| {
-			value.push_bind(id).push_bind(name);
-		})
-		.build()
-		.execute(&mut transaction)
-		.await
-		.map_err(|err| {
-			tracing::error!(
-				product,
-				error = err.to_string(),
-				"Error writing DB (Players)"
-			);
-			err
-		})
-		.map(|result| {
-			tracing::trace!(
-				product,
-				rows = result.rows_affected(),
-				"Success writing DB (Players)"
-			);
-			result
-		})?;
+		let sql = format!(
+			"INSERT INTO Riot{product}Players (Id, Name) VALUES {} ON CONFLICT DO NOTHING",
+			values(player_ids_names.len(), 2)
+		);
+		let mut query = sqlx::query(&sql);
+		for (id, name) in player_ids_names {
+			query = query.bind(*id).bind(*name);
+		}
+		query
+			.execute(&mut transaction)
+			.await
+			.inspect_err(|err| {
+				tracing::error!(
+					product,
+					error = err.to_string(),
+					"Error writing DB (Players)"
+				);
+			})
+			.inspect(|result| {
+				tracing::trace!(
+					product,
+					rows = result.rows_affected(),
+					"Success writing DB (Players)"
+				);
+			})?;
 	}
 
 	transaction.commit().await
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// Exercise every query against a freshly migrated database
+	async fn check(pool: &Pool) {
+		super::super::migrate(pool).await.unwrap();
+
+		let platform = Platform::EUW1;
+		let (start, end) = (1_700_000_000_000, 1_700_001_800_000);
+
+		insert_players(pool, "Lol", &[("puuid-a", "Old"), ("puuid-b", "B")])
+			.await
+			.unwrap();
+		insert_players(pool, "Lol", &[("puuid-a", "A")])
+			.await
+			.unwrap();
+		assert!(!contains_game(pool, "Lol", "EUW1_1", platform)
+			.await
+			.unwrap());
+
+		let player_ids_results_leagues = [
+			(
+				"puuid-a",
+				1,
+				Some((Tier::GOLD, Rank::II, 42)),
+				Some(("Ahri", 10, 2, 8)),
+			),
+			("puuid-b", 0, None, None),
+		];
+		insert_game(
+			pool,
+			"Lol",
+			"EUW1_1",
+			platform,
+			420,
+			start,
+			&player_ids_results_leagues,
+			Some(b"{}"),
+		)
+		.await
+		.unwrap();
+		assert!(contains_game(pool, "Lol", "EUW1_1", platform)
+			.await
+			.unwrap());
+		assert!(insert_game(
+			pool,
+			"Lol",
+			"EUW1_1",
+			platform,
+			420,
+			start,
+			&player_ids_results_leagues,
+			None,
+		)
+		.await
+		.is_err());
+
+		assert_eq!(count(pool, "Lol").await.unwrap(), (2, 1, 2));
+		assert_eq!(
+			get_game_payload(pool, "Lol", "EUW1_1").await.unwrap(),
+			Some((platform, b"{}".to_vec()))
+		);
+		assert_eq!(get_game_payload(pool, "Tft", "EUW1_1").await.unwrap(), None);
+		assert_eq!(
+			get_game_results(pool, "Lol", "EUW1_1", platform)
+				.await
+				.unwrap()
+				.into_iter()
+				.map(|(id, name, result, league)| (id, name, result, league.is_some()))
+				.collect::<Vec<_>>(),
+			[
+				(String::from("puuid-a"), String::from("A"), 1, true),
+				(String::from("puuid-b"), String::from("B"), 0, false),
+			]
+		);
+		assert_eq!(
+			get_players(pool, "Lol").await.unwrap(),
+			[
+				(String::from("puuid-a"), String::from("A")),
+				(String::from("puuid-b"), String::from("B")),
+			]
+		);
+		assert_eq!(
+			get_game_info(pool, "Lol", "EUW1_1").await.unwrap(),
+			Some((platform, 420, start))
+		);
+		assert_eq!(get_game_info(pool, "Tft", "EUW1_1").await.unwrap(), None);
+		assert_eq!(
+			get_leagues(pool, "Lol").await.unwrap(),
+			[(
+				String::from("puuid-a"),
+				String::from("A"),
+				420,
+				(Tier::GOLD, Rank::II, 42)
+			)]
+		);
+		assert_eq!(
+			get_last_game_timestamp(pool, "Lol", "puuid-a")
+				.await
+				.unwrap(),
+			Some(start)
+		);
+		// Storing games leaves cursors to polling
+		assert_eq!(get_cursor(pool, "Lol", "puuid-a").await.unwrap(), None);
+		set_cursor(pool, "Lol", "puuid-a", start).await.unwrap();
+		set_cursor(pool, "Lol", "puuid-a", start - 1000)
+			.await
+			.unwrap();
+		assert_eq!(
+			get_cursor(pool, "Lol", "puuid-a").await.unwrap(),
+			Some(start)
+		);
+		assert_eq!(get_cursor(pool, "Tft", "puuid-a").await.unwrap(), None);
+
+		let results = get_results(pool, "Lol", "puuid-a", start).await.unwrap();
+		assert_eq!(results.len(), 1);
+		assert_eq!((results[0].queue, results[0].result), (420, 1));
+		assert_eq!(results[0].league.map(|(.., points)| points), Some(42));
+		assert_eq!(results[0].champion.as_deref(), Some("Ahri"));
+		assert_eq!(results[0].kills_deaths_assists, Some((10, 2, 8)));
+		assert!(get_results(pool, "Lol", "puuid-a", start + 1000)
+			.await
+			.unwrap()
+			.is_empty());
+
+		// TFT results have no performance columns
+		insert_players(pool, "Tft", &[("puuid-a", "A")])
+			.await
+			.unwrap();
+		insert_game(
+			pool,
+			"Tft",
+			"EUW1_1",
+			platform,
+			1100,
+			start,
+			&[("puuid-a", 2, None, None)],
+			None,
+		)
+		.await
+		.unwrap();
+		let results = get_results(pool, "Tft", "puuid-a", start).await.unwrap();
+		assert_eq!(results.len(), 1);
+		assert_eq!((results[0].queue, results[0].result), (1100, 2));
+		assert_eq!(
+			(
+				results[0].champion.as_deref(),
+				results[0].kills_deaths_assists
+			),
+			(None, None)
+		);
+
+		let won = |result| result > 0;
+		for (index, result) in [0, 1, 1].into_iter().enumerate() {
+			let time = end + 3_600_000 * index as i64;
+			insert_game(
+				pool,
+				"Lol",
+				&format!("EUW1_{}", index + 2),
+				platform,
+				420,
+				time,
+				&[("puuid-b", result, None, None)],
+				None,
+			)
+			.await
+			.unwrap();
+		}
+		assert_eq!(
+			get_streak(pool, "Lol", "puuid-b", 420, end + 7_200_000, won)
+				.await
+				.unwrap(),
+			2
+		);
+		assert_eq!(
+			get_streak(pool, "Lol", "puuid-b", 420, end, won)
+				.await
+				.unwrap(),
+			-2
+		);
+		assert_eq!(
+			get_streak(pool, "Lol", "puuid-b", 440, end, won)
+				.await
+				.unwrap(),
+			0
+		);
+
+		// A game that failed to be stored (EUW1_5) holds the cursor back, so the
+		// next poll returns it again
+		let poll = |game_ids: &[&str]| {
+			game_ids
+				.iter()
+				.map(|id| String::from(*id))
+				.collect::<Vec<_>>()
+		};
+		assert_eq!(
+			advance_cursor(
+				pool,
+				"Lol",
+				"puuid-b",
+				platform,
+				&poll(&["EUW1_6", "EUW1_5", "EUW1_4", "EUW1_3"]),
+				&BTreeSet::default(),
+			)
+			.await
+			.unwrap(),
+			["EUW1_5", "EUW1_6"]
+		);
+		assert_eq!(
+			get_cursor(pool, "Lol", "puuid-b").await.unwrap(),
+			Some(end + 7_200_000 + 1000)
+		);
+		assert_eq!(
+			advance_cursor(
+				pool,
+				"Lol",
+				"puuid-b",
+				platform,
+				&poll(&["EUW1_6", "EUW1_5"]),
+				&BTreeSet::default(),
+			)
+			.await
+			.unwrap(),
+			["EUW1_5", "EUW1_6"]
+		);
+		insert_game(
+			pool,
+			"Lol",
+			"EUW1_5",
+			platform,
+			420,
+			end + 10_800_000,
+			&[("puuid-b", 1, None, None)],
+			None,
+		)
+		.await
+		.unwrap();
+		assert_eq!(
+			advance_cursor(
+				pool,
+				"Lol",
+				"puuid-b",
+				platform,
+				&poll(&["EUW1_6", "EUW1_5"]),
+				&BTreeSet::default(),
+			)
+			.await
+			.unwrap(),
+			["EUW1_6"]
+		);
+		assert_eq!(
+			get_cursor(pool, "Lol", "puuid-b").await.unwrap(),
+			Some(end + 10_800_000 + 1000)
+		);
+
+		// A game given up on (EUW1_6) no longer holds the cursor back
+		insert_game(
+			pool,
+			"Lol",
+			"EUW1_7",
+			platform,
+			420,
+			end + 18_000_000,
+			&[("puuid-b", 1, None, None)],
+			None,
+		)
+		.await
+		.unwrap();
+		assert_eq!(
+			advance_cursor(
+				pool,
+				"Lol",
+				"puuid-b",
+				platform,
+				&poll(&["EUW1_8", "EUW1_7", "EUW1_6"]),
+				&BTreeSet::from([String::from("EUW1_6")]),
+			)
+			.await
+			.unwrap(),
+			["EUW1_8"]
+		);
+		assert_eq!(
+			get_cursor(pool, "Lol", "puuid-b").await.unwrap(),
+			Some(end + 18_000_000 + 1000)
+		);
+
+		let history = get_history(pool, "Lol", "puuid-a", 420, end).await.unwrap();
+		assert_eq!(history.games, 1);
+		assert_eq!(history.previous_league, Some((Tier::GOLD, Rank::II, 42)));
+		let history = get_history(pool, "Lol", "puuid-a", 420, start)
+			.await
+			.unwrap();
+		assert_eq!(history.previous_league, None);
+
+		// Only the latest league of every player and queue
+		for (game_id, queue, time, league) in [
+			(
+				"EUW1_9",
+				420,
+				end + 21_600_000,
+				(Tier::PLATINUM, Rank::IV, 10),
+			),
+			("EUW1_10", 440, end, (Tier::SILVER, Rank::I, 80)),
+		] {
+			insert_game(
+				pool,
+				"Lol",
+				game_id,
+				platform,
+				queue,
+				time,
+				&[("puuid-a", 1, Some(league), None)],
+				None,
+			)
+			.await
+			.unwrap();
+		}
+		assert_eq!(
+			get_leagues(pool, "Lol").await.unwrap(),
+			[
+				(
+					String::from("puuid-a"),
+					String::from("A"),
+					420,
+					(Tier::PLATINUM, Rank::IV, 10)
+				),
+				(
+					String::from("puuid-a"),
+					String::from("A"),
+					440,
+					(Tier::SILVER, Rank::I, 80)
+				),
+			]
+		);
+
+		assert!(
+			insert_achievement(pool, "Lol", "puuid-a", "pentakill", "EUW1_1", platform)
+				.await
+				.unwrap()
+		);
+		assert!(
+			!insert_achievement(pool, "Lol", "puuid-a", "pentakill", "EUW1_2", platform)
+				.await
+				.unwrap()
+		);
+		assert_eq!(
+			get_achievements(pool, "Lol", "puuid-a").await.unwrap(),
+			[(String::from("pentakill"), start)]
+		);
+	}
+
+	#[tokio::test]
+	async fn sqlite() {
+		let pool = sqlx::any::AnyPoolOptions::new()
+			.max_connections(1)
+			.connect("sqlite::memory:")
+			.await
+			.unwrap();
+		check(&pool).await;
+	}
+
+	// Needs a database where schemas can be created, e.g.
+	// TEST_POSTGRES_URL=postgres://postgres@localhost/riot_test cargo test -- --ignored
+	#[tokio::test]
+	#[ignore = "needs TEST_POSTGRES_URL"]
+	async fn postgres() {
+		let url = std::env::var("TEST_POSTGRES_URL").expect("TEST_POSTGRES_URL");
+		// A single connection, so that every query uses the throwaway schema
+		let pool = sqlx::any::AnyPoolOptions::new()
+			.max_connections(1)
+			.connect(&url)
+			.await
+			.unwrap();
+		let schema = format!(
+			"riot_test_{}_{}",
+			std::process::id(),
+			chrono::Utc::now().timestamp_millis()
+		);
+		sqlx::Executor::execute(
+			&pool,
+			format!("CREATE SCHEMA {schema}; SET search_path TO {schema}").as_str(),
+		)
+		.await
+		.unwrap();
+		check(&pool).await;
+		sqlx::Executor::execute(&pool, format!("DROP SCHEMA {schema} CASCADE").as_str())
+			.await
+			.unwrap();
+	}
+}
@@ -1,27 +1,167 @@
+use std::collections::BTreeSet;
+
 use super::{
 	riot::{
 		tft::{Game, League, Participant, Player},
-		Platform, Rank, Tier,
+		Platform, QueueType, Rank, Tier,
 	},
-	Result, SqlitePool,
+	GameResult, History, Pool, Result,
 };
 
 const PRODUCT: &str = "Tft";
 
-pub async fn create_tables(pool: &SqlitePool) -> Result<()> {
-	super::create_tables(pool, PRODUCT).await
+// Number of stored players, games and game results
+pub async fn count(pool: &Pool) -> Result<(i64, i64, i64)> {
+	super::count(pool, PRODUCT).await
 }
 
-pub async fn contains_game(pool: &SqlitePool, game_id: &str, platform: Platform) -> Result<bool> {
+pub async fn contains_game(pool: &Pool, game_id: &str, platform: Platform) -> Result<bool> {
 	super::contains_game(pool, PRODUCT, game_id, platform).await
 }
 
+pub async fn get_last_game_timestamp(pool: &Pool, player: &Player) -> Result<Option<i64>> {
+	super::get_last_game_timestamp(pool, PRODUCT, &player.puuid).await
+}
+
+pub async fn get_cursor(pool: &Pool, player: &Player) -> Result<Option<i64>> {
+	super::get_cursor(pool, PRODUCT, &player.puuid).await
+}
+
+pub async fn set_cursor(pool: &Pool, player: &Player, timestamp: i64) -> Result<()> {
+	super::set_cursor(pool, PRODUCT, &player.puuid, timestamp).await
+}
+
+// Game identifiers of a poll (newest first) that are not stored nor given up
+// on, oldest first, after moving the cursor of the player up to the oldest of them
+pub async fn advance_cursor(
+	pool: &Pool,
+	player: &Player,
+	platform: Platform,
+	game_ids: &[String],
+	given_up: &BTreeSet<String>,
+) -> Result<Vec<String>> {
+	super::advance_cursor(pool, PRODUCT, &player.puuid, platform, game_ids, given_up).await
+}
+
+// Results of a player in the games started since `start_time` (milliseconds), oldest first
+pub async fn get_results(pool: &Pool, player: &Player, start_time: i64) -> Result<Vec<GameResult>> {
+	super::get_results(pool, PRODUCT, &player.puuid, start_time).await
+}
+
+// Id and name of every stored player
+pub async fn get_players(pool: &Pool) -> Result<Vec<(String, String)>> {
+	super::get_players(pool, PRODUCT).await
+}
+
+// Results of a player by PUUID, as `get_results`
+pub async fn get_results_by_id(
+	pool: &Pool,
+	player_id: &str,
+	start_time: i64,
+) -> Result<Vec<GameResult>> {
+	super::get_results(pool, PRODUCT, player_id, start_time).await
+}
+
+// Platform, queue, start (milliseconds) and stored players' PUUID, name, result
+// and league of a stored game
+pub async fn get_game_summary(
+	pool: &Pool,
+	game_id: &str,
+) -> Result<
+	Option<(
+		Platform,
+		i32,
+		i64,
+		Vec<(String, String, i32, Option<(Tier, Rank, i32)>)>,
+	)>,
+> {
+	let Some((platform, queue, timestamp)) = super::get_game_info(pool, PRODUCT, game_id).await?
+	else {
+		return Ok(None);
+	};
+	let results = super::get_game_results(pool, PRODUCT, game_id, platform).await?;
+	Ok(Some((platform, queue, timestamp, results)))
+}
+
+// PUUID, name, queue and latest known league of every player in every ranked
+// queue they played
+pub async fn get_leagues(pool: &Pool) -> Result<Vec<(String, String, i32, (Tier, Rank, i32))>> {
+	super::get_leagues(pool, PRODUCT).await
+}
+
+// Current streak of a player in the queue of a game, up to that game: wins are
+// positive and losses negative
+pub async fn get_streak(pool: &Pool, player: &Player, game: &Game) -> Result<i32> {
+	get_streak_by_id(pool, &player.puuid, game).await
+}
+
+async fn get_streak_by_id(pool: &Pool, player_id: &str, game: &Game) -> Result<i32> {
+	super::get_streak(
+		pool,
+		PRODUCT,
+		player_id,
+		game.info.queue_id,
+		game.info.game_datetime,
+		won,
+	)
+	.await
+}
+
+// Stored games of a player and their league before a game
+pub async fn get_history(pool: &Pool, player: &Player, game: &Game) -> Result<History> {
+	super::get_history(
+		pool,
+		PRODUCT,
+		&player.puuid,
+		game.info.queue_id,
+		game.info.game_datetime,
+	)
+	.await
+}
+
+// Whether the achievement was not unlocked yet
+pub async fn insert_achievement(
+	pool: &Pool,
+	player: &Player,
+	achievement: &str,
+	game: &Game,
+	platform: Platform,
+) -> Result<bool> {
+	super::insert_achievement(
+		pool,
+		PRODUCT,
+		&player.puuid,
+		achievement,
+		&game.metadata.match_id,
+		platform,
+	)
+	.await
+}
+
+// Achievements of a player and the start of the game unlocking them
+// (milliseconds), oldest first
+pub async fn get_achievements(pool: &Pool, player: &Player) -> Result<Vec<(String, i64)>> {
+	super::get_achievements(pool, PRODUCT, &player.puuid).await
+}
+
+// A top 4 placement counts as a win
+fn won(result: i32) -> bool {
+	result <= 4
+}
+
 pub async fn insert_game<T>(
-	pool: &SqlitePool,
+	pool: &Pool,
 	game: &Game,
 	platform: Platform,
 	players_participants_leagues_: &[(&Player, &Participant, Option<League>, T)],
+	store_payload: bool,
 ) -> Result<()> {
+	let payload = match store_payload {
+		true => {
+			Some(serde_json::to_vec(game).map_err(|err| sqlx::Error::Protocol(err.to_string()))?)
+		}
+		false => None,
+	};
 	super::insert_game(
 		pool,
 		PRODUCT,
@@ -42,15 +182,92 @@ pub async fn insert_game<T>(
 							league.league_points.unwrap_or(-1),
 						)
 					}),
+					None,
 				)
 			})
 			.collect::<Vec<_>>(),
+		payload.as_deref(),
 	)
 	.await
 }
 
+// Stored game with the participants and leagues of its stored players, only
+// available if its payload was stored
+pub async fn get_game(
+	pool: &Pool,
+	game_id: &str,
+) -> Result<
+	Option<(
+		Game,
+		Platform,
+		Vec<(Player, Participant, Option<League>, i32)>,
+	)>,
+> {
+	let Some((platform, payload)) = super::get_game_payload(pool, PRODUCT, game_id).await? else {
+		return Ok(None);
+	};
+	let game: Game =
+		serde_json::from_slice(&payload).map_err(|err| sqlx::Error::Decode(err.into()))?;
+	let queue_type = [
+		QueueType::RANKED_TFT,
+		QueueType::RANKED_TFT_TURBO,
+		QueueType::RANKED_TFT_DOUBLE_UP,
+	]
+	.into_iter()
+	.find(|queue_type| super::riot::are_same_queue_id(queue_type, game.info.queue_id));
+
+	let mut players_participants_leagues_streaks = Vec::default();
+	for (puuid, name, _, league) in
+		super::get_game_results(pool, PRODUCT, game_id, platform).await?
+	{
+		let streak = get_streak_by_id(pool, &puuid, &game).await?;
+		let Some(participant) = game
+			.info
+			.participants
+			.iter()
+			.find(|participant| participant.puuid == puuid)
+			.cloned()
+		else {
+			continue;
+		};
+		// Only the stored fields are known
+		let player = Player {
+			account_id: String::default(),
+			profile_icon_id: 0,
+			revision_date: 0,
+			name,
+			id: String::default(),
+			puuid,
+			summoner_level: 0,
+		};
+		let league =
+			league
+				.zip(queue_type.clone())
+				.map(|((tier, rank, league_points), queue_type)| League {
+					league_id: None,
+					summoner_id: String::default(),
+					summoner_name: player.name.clone(),
+					queue_type,
+					rated_tier: None,
+					rated_rating: None,
+					tier: Some(tier),
+					rank: Some(rank),
+					league_points: Some(league_points),
+					wins: 0,
+					losses: 0,
+					hot_streak: None,
+					veteran: None,
+					fresh_blood: None,
+					inactive: None,
+					mini_series: None,
+				});
+		players_participants_leagues_streaks.push((player, participant, league, streak));
+	}
+	Ok(Some((game, platform, players_participants_leagues_streaks)))
+}
+
 pub async fn insert_players<T>(
-	pool: &SqlitePool,
+	pool: &Pool,
 	players_platforms_: &[((Player, Platform), T)],
 ) -> Result<()> {
 	super::insert_players(
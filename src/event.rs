@@ -0,0 +1,261 @@
+use hmac::Mac;
+use serde::Serialize;
+use tokio::{
+	io::AsyncWriteExt,
+	time::{Duration, Instant},
+};
+
+use super::{api::riot as riot_api, config, db, db::riot as riot_db, message};
+
+pub mod lol;
+pub mod tft;
+
+// Stored game, as posted to webhooks and appended to the event file
+#[derive(Debug, Serialize)]
+pub struct Event {
+	// "lol" or "tft"
+	pub product: &'static str,
+	pub platform: String,
+	pub match_id: String,
+	pub queue: u16,
+	// Milliseconds
+	pub timestamp: i64,
+	// Tracked participants only
+	pub participants: Vec<Participant>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Participant {
+	pub puuid: String,
+	pub name: String,
+	// LOL: 1 for a win and 0 for a loss, TFT: placement
+	pub result: i32,
+	// TFT: top 4
+	pub win: bool,
+	pub league: Option<League>,
+	// Since the previous known league of the queue, across divisions and tiers
+	pub lp_delta: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct League {
+	pub tier: String,
+	pub rank: String,
+	pub lp: i32,
+}
+
+fn league(
+	tier: Option<riot_api::Tier>,
+	rank: Option<riot_api::Rank>,
+	points: i32,
+	history: Option<&riot_db::History>,
+) -> (Option<League>, Option<i32>) {
+	let Some((tier, rank)) = tier.zip(rank).filter(|(tier, _)| tier.is_ranked()) else {
+		return (None, None);
+	};
+	let lp_delta = history
+		.and_then(|history| history.previous_league)
+		.filter(|(previous_tier, ..)| previous_tier.is_ranked())
+		.map(|(previous_tier, previous_rank, previous_points)| {
+			message::riot::total_league_points(tier, rank, points)
+				- message::riot::total_league_points(previous_tier, previous_rank, previous_points)
+		});
+	(
+		Some(League {
+			tier: String::from(tier.as_ref()),
+			rank: rank.to_string(),
+			lp: points,
+		}),
+		lp_delta,
+	)
+}
+
+// Appended to the event file and posted to every webhook, failed posts are
+// retried with exponential backoff. Past `deadline` (at shutdown), requests are
+// cut short and not retried.
+pub async fn deliver(
+	client: &reqwest::Client,
+	events: &config::Events,
+	event: &Event,
+	deadline: Option<Instant>,
+) {
+	let payload = match serde_json::to_string(event) {
+		Ok(payload) => payload,
+		Err(err) => {
+			tracing::error!(error = err.to_string(), "Error serializing event");
+			return;
+		}
+	};
+
+	if let Some(file) = &events.file {
+		let result = async {
+			tokio::fs::OpenOptions::new()
+				.create(true)
+				.append(true)
+				.open(file)
+				.await?
+				.write_all(format!("{payload}\n").as_bytes())
+				.await
+		}
+		.await;
+		result.unwrap_or_else(|err| {
+			tracing::error!(error = err.to_string(), file, "Error writing event");
+		});
+	}
+
+	for webhook in &events.webhooks {
+		let mut attempt = 0;
+		loop {
+			let mut request = client
+				.post(&webhook.url)
+				.header(reqwest::header::CONTENT_TYPE, "application/json")
+				.body(payload.clone());
+			if let Some(secret) = &webhook.secret {
+				request = request.header("X-Signature-256", sign(secret.expose(), &payload));
+			}
+			if let Some(deadline) = deadline {
+				request = request.timeout(deadline.saturating_duration_since(Instant::now()));
+			}
+			let Err(err) = request
+				.send()
+				.await
+				.and_then(reqwest::Response::error_for_status)
+			else {
+				break;
+			};
+			// Webhook URLs may contain credentials, so they are not logged
+			tracing::error!(
+				error = err.without_url().to_string(),
+				match_id = event.match_id,
+				attempt,
+				"Error posting event"
+			);
+			let backoff = Duration::from_secs(1 << attempt.min(10));
+			if attempt == events.retries
+				|| deadline.is_some_and(|deadline| Instant::now() + backoff >= deadline)
+			{
+				break;
+			}
+			tokio::time::sleep(backoff).await;
+			attempt += 1;
+		}
+	}
+}
+
+// "sha256=" and the hexadecimal HMAC-SHA256 of the payload
+fn sign(secret: &str, payload: &str) -> String {
+	let mut mac = hmac::Hmac::<sha2::Sha256>::new_from_slice(secret.as_bytes())
+		.expect("HMAC accepts keys of any length");
+	mac.update(payload.as_bytes());
+	let signature = mac
+		.finalize()
+		.into_bytes()
+		.iter()
+		.map(|byte| format!("{byte:02x}"))
+		.collect::<String>();
+	format!("sha256={signature}")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{super::notifier::tests::stand_in_answering, *};
+
+	fn event() -> Event {
+		Event {
+			product: "lol",
+			platform: String::from("EUW1"),
+			match_id: String::from("EUW1_1"),
+			queue: 420,
+			timestamp: 1_700_000_000_000,
+			participants: Vec::default(),
+		}
+	}
+
+	fn webhook(url: String, secret: Option<&str>) -> config::Webhook {
+		serde_json::from_value(serde_json::json!({ "url": url, "secret": secret })).unwrap()
+	}
+
+	#[tokio::test]
+	async fn webhooks() {
+		let (url, mut requests) = stand_in_answering(vec![(200, "")]).await;
+		let events = config::Events {
+			webhooks: vec![
+				webhook(format!("{url}/signed"), Some("secret")),
+				webhook(format!("{url}/unsigned"), None),
+			],
+			..config::Events::default()
+		};
+		deliver(&reqwest::Client::new(), &events, &event(), None).await;
+
+		let (head, body) = requests.recv().await.unwrap();
+		assert!(head.starts_with("POST /signed "));
+		let body = String::from_utf8(body).unwrap();
+		assert!(head
+			.to_lowercase()
+			.contains(&format!("x-signature-256: {}", sign("secret", &body))));
+		let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+		assert_eq!(body["match_id"], "EUW1_1");
+		assert_eq!(body["queue"], 420);
+
+		let (head, _) = requests.recv().await.unwrap();
+		assert!(head.starts_with("POST /unsigned "));
+		assert!(!head.to_lowercase().contains("x-signature-256"));
+	}
+
+	#[tokio::test]
+	async fn retries() {
+		let (url, mut requests) = stand_in_answering(vec![(503, ""), (200, "")]).await;
+		let events = config::Events {
+			webhooks: vec![webhook(url, None)],
+			retries: 3,
+			..config::Events::default()
+		};
+		// Once after 1 second, until it succeeds
+		deliver(&reqwest::Client::new(), &events, &event(), None).await;
+		for _ in 0..2 {
+			requests.recv().await.unwrap();
+		}
+		assert!(requests.try_recv().is_err());
+
+		// Not past the deadline
+		let (url, mut requests) = stand_in_answering(vec![(500, "")]).await;
+		let events = config::Events {
+			webhooks: vec![webhook(url, None)],
+			..events
+		};
+		let deadline = Instant::now() + Duration::from_millis(500);
+		deliver(&reqwest::Client::new(), &events, &event(), Some(deadline)).await;
+		requests.recv().await.unwrap();
+		assert!(requests.try_recv().is_err());
+	}
+
+	#[tokio::test]
+	async fn file() {
+		let file = std::env::temp_dir().join(format!("events-{}.ndjson", std::process::id()));
+		let _ = std::fs::remove_file(&file);
+		let events = config::Events {
+			file: Some(file.to_string_lossy().into_owned()),
+			..config::Events::default()
+		};
+		deliver(&reqwest::Client::new(), &events, &event(), None).await;
+		deliver(&reqwest::Client::new(), &events, &event(), None).await;
+
+		let lines = std::fs::read_to_string(&file).unwrap();
+		std::fs::remove_file(&file).unwrap();
+		let lines = lines.lines().collect::<Vec<_>>();
+		assert_eq!(lines.len(), 2);
+		for line in lines {
+			let event: serde_json::Value = serde_json::from_str(line).unwrap();
+			assert_eq!(event["product"], "lol");
+		}
+	}
+
+	#[test]
+	fn signature() {
+		// RFC 4231, test case 2
+		assert_eq!(
+			sign("Jefe", "what do ya want for nothing?"),
+			"sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+		);
+	}
+}
@@ -0,0 +1,45 @@
+use super::{
+	db,
+	riot_api::lol::{Game, League, Participant, Player},
+	riot_api::Platform,
+	Event,
+};
+
+// Event of a stored game, with the league points each tracked participant won
+// or lost
+pub async fn generate<T>(
+	pool: &db::Pool,
+	game: &Game,
+	platform: Platform,
+	players_participants_leagues_: &[(&Player, &Participant, Option<League>, T)],
+) -> Event {
+	let mut participants = Vec::default();
+	for (player, participant, league, ..) in players_participants_leagues_ {
+		let history = db::riot::lol::get_history(pool, player, game).await.ok();
+		let (league, lp_delta) = match league {
+			Some(league) => super::league(
+				league.tier,
+				league.rank,
+				league.league_points,
+				history.as_ref(),
+			),
+			None => (None, None),
+		};
+		participants.push(super::Participant {
+			puuid: player.puuid.clone(),
+			name: player.name.clone(),
+			result: i32::from(participant.win),
+			win: participant.win,
+			league,
+			lp_delta,
+		});
+	}
+	Event {
+		product: "lol",
+		platform: platform.to_string(),
+		match_id: game.metadata.match_id.clone(),
+		queue: game.info.queue_id.0,
+		timestamp: game.info.game_start_timestamp,
+		participants,
+	}
+}
@@ -0,0 +1,45 @@
+use super::{
+	db,
+	riot_api::tft::{Game, League, Participant, Player},
+	riot_api::Platform,
+	Event,
+};
+
+// Event of a stored game, with the league points each tracked participant won
+// or lost
+pub async fn generate<T>(
+	pool: &db::Pool,
+	game: &Game,
+	platform: Platform,
+	players_participants_leagues_: &[(&Player, &Participant, Option<League>, T)],
+) -> Event {
+	let mut participants = Vec::default();
+	for (player, participant, league, ..) in players_participants_leagues_ {
+		let history = db::riot::tft::get_history(pool, player, game).await.ok();
+		let (league, lp_delta) = match league {
+			Some(league) => super::league(
+				league.tier,
+				league.rank,
+				league.league_points.unwrap_or(-1),
+				history.as_ref(),
+			),
+			None => (None, None),
+		};
+		participants.push(super::Participant {
+			puuid: player.puuid.clone(),
+			name: player.name.clone(),
+			result: participant.placement,
+			win: participant.placement <= 4,
+			league,
+			lp_delta,
+		});
+	}
+	Event {
+		product: "tft",
+		platform: platform.to_string(),
+		match_id: game.metadata.match_id.clone(),
+		queue: u16::try_from(game.info.queue_id).unwrap_or_default(),
+		timestamp: game.info.game_datetime,
+		participants,
+	}
+}
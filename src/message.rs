@@ -1,4 +1,60 @@
+use super::achievement;
 use super::api;
 use super::config;
+use super::db;
 
+pub use rich_text::RichText;
+
+pub mod rich_text;
 pub mod riot;
+
+// Content sent to a chat, in Telegram HTML, which other sinks render through
+// `RichText`
+pub enum Message {
+	Text(String),
+	// PNG image and caption
+	Photo(Vec<u8>, String),
+}
+
+impl Message {
+	pub fn html(text: &str) -> Self {
+		Message::Text(String::from(text))
+	}
+
+	pub fn photo(photo: Vec<u8>, caption: &str) -> Self {
+		Message::Photo(photo, String::from(caption))
+	}
+}
+
+// Text to be inserted in an HTML message
+pub fn escape(text: &str) -> String {
+	text.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+}
+
+// Images are rendered with a bundled font, so they look the same everywhere
+pub fn register_fonts() {
+	static REGISTER: std::sync::Once = std::sync::Once::new();
+	REGISTER.call_once(|| {
+		plotters::style::register_font(
+			"sans-serif",
+			plotters::style::FontStyle::Normal,
+			include_bytes!("../assets/DejaVuSans.ttf"),
+		)
+		.unwrap_or_else(|_| tracing::error!("Error loading bundled font"));
+	});
+}
+
+// PNG of an RGB buffer
+pub fn encode_png(buffer: &[u8], width: u32, height: u32) -> Result<Vec<u8>, String> {
+	let mut png = Vec::default();
+	let mut encoder = png::Encoder::new(&mut png, width, height);
+	encoder.set_color(png::ColorType::Rgb);
+	encoder.set_depth(png::BitDepth::Eight);
+	encoder
+		.write_header()
+		.and_then(|mut writer| writer.write_image_data(buffer))
+		.map_err(|err| format!("Error encoding image: {err}"))?;
+	Ok(png)
+}
@@ -0,0 +1,146 @@
+// Formatting of a span of text
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Style {
+	pub bold: bool,
+	pub italic: bool,
+	pub underline: bool,
+	pub strikethrough: bool,
+	pub code: bool,
+	pub link: Option<String>,
+}
+
+// Text split in spans of a single style, rendered by every sink in its own markup
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RichText(pub Vec<(String, Style)>);
+
+impl RichText {
+	// Templates are written in the HTML subset of Telegram: b, strong, i, em, u,
+	// ins, s, strike, del, code, pre and a. Other tags are dropped.
+	pub fn from_html(html: &str) -> Self {
+		let mut spans: Vec<(String, Style)> = Vec::default();
+		let mut tags: Vec<(String, Option<String>)> = Vec::default();
+		let mut rest = html;
+		while !rest.is_empty() {
+			let (text, tag) = match rest.find('<') {
+				Some(0) => match rest.find('>') {
+					Some(end) => {
+						let tag = &rest[1..end];
+						rest = &rest[end + 1..];
+						("", Some(tag))
+					}
+					None => (std::mem::take(&mut rest), None),
+				},
+				Some(start) => {
+					let text = &rest[..start];
+					rest = &rest[start..];
+					(text, None)
+				}
+				None => (std::mem::take(&mut rest), None),
+			};
+
+			if !text.is_empty() {
+				let text = unescape(text);
+				let style = style(&tags);
+				match spans.last_mut() {
+					Some((last, last_style)) if *last_style == style => last.push_str(&text),
+					_ => spans.push((text, style)),
+				}
+			}
+
+			match tag.map(str::trim) {
+				Some(tag) if tag.starts_with('/') => {
+					let name = tag[1..].trim().to_lowercase();
+					if let Some(index) = tags.iter().rposition(|(open, _)| *open == name) {
+						tags.remove(index);
+					}
+				}
+				Some(tag) => {
+					let name = tag
+						.split_whitespace()
+						.next()
+						.unwrap_or_default()
+						.to_lowercase();
+					let href = tag
+						.split_once("href=\"")
+						.and_then(|(_, href)| href.split_once('"'))
+						.map(|(href, _)| unescape(href));
+					tags.push((name, href));
+				}
+				None => {}
+			}
+		}
+		RichText(spans)
+	}
+
+	pub fn to_plain(&self) -> String {
+		self.0.iter().map(|(text, _)| text.as_str()).collect()
+	}
+
+	pub fn to_html(&self) -> String {
+		self.render(|text, style| {
+			let mut html = super::escape(text);
+			for (enabled, tag) in [
+				(style.code, "code"),
+				(style.strikethrough, "s"),
+				(style.underline, "u"),
+				(style.italic, "i"),
+				(style.bold, "b"),
+			] {
+				if enabled {
+					html = format!("<{tag}>{html}</{tag}>");
+				}
+			}
+			match &style.link {
+				Some(link) => format!("<a href=\"{}\">{html}</a>", super::escape(link)),
+				None => html,
+			}
+		})
+	}
+
+	// Every line of every span is rendered on its own without its surrounding
+	// whitespace, as Markdown-like markups do not allow them inside markers
+	pub fn render(&self, render_span: impl Fn(&str, &Style) -> String) -> String {
+		let mut rendered = String::default();
+		for (text, style) in &self.0 {
+			for (index, line) in text.split('\n').enumerate() {
+				if index > 0 {
+					rendered.push('\n');
+				}
+				let content = line.trim();
+				if content.is_empty() {
+					rendered.push_str(line);
+					continue;
+				}
+				let start = line.len() - line.trim_start().len();
+				rendered.push_str(&line[..start]);
+				rendered.push_str(&render_span(content, style));
+				rendered.push_str(&line[start + content.len()..]);
+			}
+		}
+		rendered
+	}
+}
+
+fn style(tags: &[(String, Option<String>)]) -> Style {
+	let open = |names: &[&str]| tags.iter().any(|(tag, _)| names.contains(&tag.as_str()));
+	Style {
+		bold: open(&["b", "strong"]),
+		italic: open(&["i", "em"]),
+		underline: open(&["u", "ins"]),
+		strikethrough: open(&["s", "strike", "del"]),
+		code: open(&["code", "pre"]),
+		link: tags
+			.iter()
+			.rev()
+			.find(|(tag, _)| tag == "a")
+			.and_then(|(_, href)| href.clone()),
+	}
+}
+
+fn unescape(text: &str) -> String {
+	text.replace("&lt;", "<")
+		.replace("&gt;", ">")
+		.replace("&quot;", "\"")
+		.replace("&#39;", "'")
+		.replace("&amp;", "&")
+}
@@ -1,4 +1,53 @@
-use super::{api::riot as riot_api, config::riot as riot_config};
+use super::{achievement, api::riot as riot_api, config::riot as riot_config, db::riot as riot_db};
 
+use super::{encode_png, escape, register_fonts};
+
+pub mod achievements;
+pub mod card;
+pub mod graph;
+pub mod locale;
 pub mod lol;
+pub mod placeholders;
+pub mod stats;
 pub mod tft;
+
+// League points counted from the bottom of the ladder, apex tiers share a
+// single ladder above Diamond I
+pub fn total_league_points(tier: riot_api::Tier, rank: riot_api::Rank, points: i32) -> i32 {
+	let tiers = [
+		riot_api::Tier::IRON,
+		riot_api::Tier::BRONZE,
+		riot_api::Tier::SILVER,
+		riot_api::Tier::GOLD,
+		riot_api::Tier::PLATINUM,
+		riot_api::Tier::DIAMOND,
+	];
+	match tiers.iter().position(|standard| *standard == tier) {
+		Some(index) => {
+			let divisions = 4 - i32::from(u8::from(rank)).min(4);
+			(index as i32 * 4 + divisions) * 100 + points
+		}
+		None if tier.is_apex() => tiers.len() as i32 * 400 + points,
+		None => 0,
+	}
+}
+
+// Results with a known league of the queue most recently played ranked
+pub fn ranked_results(
+	results: &[riot_db::GameResult],
+) -> Vec<(&riot_db::GameResult, (riot_api::Tier, riot_api::Rank, i32))> {
+	let Some(queue) = results
+		.iter()
+		.rev()
+		.find(|result| result.league.is_some())
+		.map(|result| result.queue)
+	else {
+		return Vec::default();
+	};
+	results
+		.iter()
+		.filter(|result| result.queue == queue)
+		.filter_map(|result| result.league.map(|league| (result, league)))
+		.filter(|(_, (tier, ..))| *tier != riot_api::Tier::UNRANKED)
+		.collect()
+}
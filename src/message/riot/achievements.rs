@@ -0,0 +1,28 @@
+use super::{achievement, riot_config::Locale};
+
+// Unlocked achievements of a player, oldest first
+pub fn generate(
+	name: &str,
+	product: &str,
+	achievements: &[(String, i64)],
+	locale: &Locale,
+) -> String {
+	let mut lines = vec![format!(
+		"🏅 <b>{}</b> · {product} achievements",
+		super::escape(name)
+	)];
+	if achievements.is_empty() {
+		lines.push(String::from("No achievements yet"));
+	}
+	for (achievement, timestamp) in achievements {
+		lines.push(format!(
+			"- {} ({})",
+			achievement::describe(achievement, locale),
+			chrono::naive::NaiveDateTime::from_timestamp_millis(*timestamp)
+				.map_or_else(String::default, |datetime| datetime
+					.format("%d/%m/%Y")
+					.to_string())
+		));
+	}
+	lines.join("\n")
+}
@@ -0,0 +1,361 @@
+use std::collections::BTreeMap;
+
+use plotters::{coord::Shift, prelude::*};
+
+use super::{
+	riot_api::{lol, tft, Platform, Team},
+	riot_config::Locale,
+};
+
+const WIDTH: u32 = 960;
+const ROW: i32 = 40;
+
+const BACKGROUND: RGBColor = RGBColor(20, 24, 31);
+const HIGHLIGHT: RGBColor = RGBColor(45, 52, 66);
+const MUTED: RGBColor = RGBColor(140, 146, 160);
+const WIN: RGBColor = RGBColor(46, 160, 67);
+const LOSS: RGBColor = RGBColor(218, 54, 51);
+const BAR: RGBColor = RGBColor(230, 170, 60);
+
+type Area<'a> = DrawingArea<BitMapBackend<'a>, Shift>;
+type DrawResult<'a> =
+	Result<(), DrawingAreaErrorKind<<BitMapBackend<'a> as DrawingBackend>::ErrorType>>;
+
+// Scoreboard of both teams with the tracked players highlighted, and its caption
+pub fn generate_lol(
+	game: &lol::Game,
+	platform: Platform,
+	players_participants_leagues_streaks: &[(
+		lol::Player,
+		lol::Participant,
+		Option<lol::League>,
+		i32,
+	)],
+	locale: &Locale,
+) -> Result<(Vec<u8>, String), String> {
+	super::register_fonts();
+
+	let puuids = players_participants_leagues_streaks
+		.iter()
+		.map(|(player, ..)| player.puuid.as_str())
+		.collect::<Vec<_>>();
+	let height = (90
+		+ lol_teams(game)
+			.values()
+			.map(|participants| 36 + participants.len() as i32 * ROW)
+			.sum::<i32>()
+		+ 10) as u32;
+	let mut buffer = vec![0; (WIDTH * height * 3) as usize];
+	draw_lol(
+		BitMapBackend::with_buffer(&mut buffer, (WIDTH, height)).into_drawing_area(),
+		game,
+		platform,
+		&puuids,
+		locale,
+	)
+	.map_err(|err| format!("Error drawing card: {err}"))?;
+
+	let caption = format!(
+		"{} · {} · {} min",
+		players_participants_leagues_streaks
+			.iter()
+			.map(|(player, participant, ..)| format!(
+				"{} <b>{}</b>",
+				if participant.win { "✅" } else { "❌" },
+				super::escape(&player.name)
+			))
+			.collect::<Vec<_>>()
+			.join(" · "),
+		super::lol::get_queue_or_mode_string(game, locale),
+		game.info.game_duration / 60
+	);
+	Ok((super::encode_png(&buffer, WIDTH, height)?, caption))
+}
+
+fn draw_lol<'a>(
+	root: Area<'a>,
+	game: &lol::Game,
+	platform: Platform,
+	puuids: &[&str],
+	locale: &Locale,
+) -> DrawResult<'a> {
+	root.fill(&BACKGROUND)?;
+
+	let won = game
+		.info
+		.participants
+		.iter()
+		.find(|participant| puuids.contains(&participant.puuid.as_str()))
+		.is_some_and(|participant| participant.win);
+	text(
+		&root,
+		&super::locale::label(locale, if won { "VICTORY" } else { "DEFEAT" }),
+		(24, 24),
+		36,
+		if won { WIN } else { LOSS },
+	)?;
+	text(
+		&root,
+		&format!(
+			"{} · {} min · {}",
+			super::lol::get_queue_or_mode_string(game, locale),
+			game.info.game_duration / 60,
+			super::locale::region(locale, platform)
+		),
+		(WIDTH as i32 - 360, 36),
+		20,
+		MUTED,
+	)?;
+
+	let max_damage = game
+		.info
+		.participants
+		.iter()
+		.map(|participant| participant.total_damage_dealt_to_champions)
+		.max()
+		.unwrap_or_default()
+		.max(1);
+
+	let mut y = 90;
+	for (team, participants) in lol_teams(game) {
+		let team_won = participants.iter().any(|participant| participant.win);
+		text(
+			&root,
+			&format!(
+				"{} · {}",
+				super::locale::label(
+					locale,
+					match team {
+						Team::BLUE => "Blue team",
+						Team::RED => "Red team",
+						Team::OTHER => "Other team",
+					}
+				),
+				super::locale::label(locale, if team_won { "Victory" } else { "Defeat" })
+			),
+			(24, y + 8),
+			18,
+			if team_won { WIN } else { LOSS },
+		)?;
+		y += 36;
+
+		for participant in participants {
+			if puuids.contains(&participant.puuid.as_str()) {
+				root.draw(&Rectangle::new(
+					[(12, y), (WIDTH as i32 - 12, y + ROW - 4)],
+					HIGHLIGHT.filled(),
+				))?;
+			}
+			text(&root, &participant.summoner_name, (24, y + 8), 18, WHITE)?;
+			text(&root, &participant.champion_name, (300, y + 8), 18, WHITE)?;
+			text(
+				&root,
+				&format!(
+					"{}/{}/{}",
+					participant.kills, participant.deaths, participant.assists
+				),
+				(480, y + 8),
+				18,
+				WHITE,
+			)?;
+
+			// Damage to champions relative to the highest of the game
+			let width = 220 * participant.total_damage_dealt_to_champions / max_damage;
+			root.draw(&Rectangle::new(
+				[(600, y + 10), (600 + width.max(2), y + ROW - 14)],
+				BAR.filled(),
+			))?;
+			text(
+				&root,
+				&participant.total_damage_dealt_to_champions.to_string(),
+				(836, y + 8),
+				18,
+				MUTED,
+			)?;
+			y += ROW;
+		}
+	}
+
+	root.present()
+}
+
+// Participants by team, blue first
+fn lol_teams(game: &lol::Game) -> BTreeMap<Team, Vec<&lol::Participant>> {
+	let mut teams = BTreeMap::<_, Vec<_>>::default();
+	for participant in &game.info.participants {
+		teams
+			.entry(participant.team_id)
+			.or_default()
+			.push(participant);
+	}
+	teams
+}
+
+// Final standings with level, traits and units, tracked players highlighted,
+// and its caption
+pub fn generate_tft(
+	game: &tft::Game,
+	players_participants_leagues_streaks: &[(
+		tft::Player,
+		tft::Participant,
+		Option<tft::League>,
+		i32,
+	)],
+	locale: &Locale,
+) -> Result<(Vec<u8>, String), String> {
+	super::register_fonts();
+
+	let players = players_participants_leagues_streaks
+		.iter()
+		.map(|(player, ..)| (player.puuid.as_str(), player.name.as_str()))
+		.collect::<Vec<_>>();
+	let height = (90 + game.info.participants.len() as i32 * (ROW + 20) + 10) as u32;
+	let mut buffer = vec![0; (WIDTH * height * 3) as usize];
+	draw_tft(
+		BitMapBackend::with_buffer(&mut buffer, (WIDTH, height)).into_drawing_area(),
+		game,
+		&players,
+		locale,
+	)
+	.map_err(|err| format!("Error drawing card: {err}"))?;
+
+	let caption = format!(
+		"{} · {} · {:.0} min",
+		players_participants_leagues_streaks
+			.iter()
+			.map(|(player, participant, ..)| format!(
+				"#{} <b>{}</b>",
+				participant.placement,
+				super::escape(&player.name)
+			))
+			.collect::<Vec<_>>()
+			.join(" · "),
+		super::tft::get_queue_or_mode_string(game, locale),
+		game.info.game_length / 60.0
+	);
+	Ok((super::encode_png(&buffer, WIDTH, height)?, caption))
+}
+
+fn draw_tft<'a>(
+	root: Area<'a>,
+	game: &tft::Game,
+	players: &[(&str, &str)],
+	locale: &Locale,
+) -> DrawResult<'a> {
+	root.fill(&BACKGROUND)?;
+
+	text(
+		&root,
+		&format!(
+			"{} · {:.0} min",
+			super::tft::get_queue_or_mode_string(game, locale),
+			game.info.game_length / 60.0
+		),
+		(24, 28),
+		28,
+		WHITE,
+	)?;
+
+	let mut participants = game.info.participants.iter().collect::<Vec<_>>();
+	participants.sort_by_key(|participant| participant.placement);
+
+	let mut y = 90;
+	for participant in participants {
+		let name = players
+			.iter()
+			.find(|(puuid, _)| *puuid == participant.puuid)
+			.map(|(_, name)| *name);
+		if name.is_some() {
+			root.draw(&Rectangle::new(
+				[(12, y - 4), (WIDTH as i32 - 12, y + ROW + 12)],
+				HIGHLIGHT.filled(),
+			))?;
+		}
+
+		text(
+			&root,
+			&format!("#{}", participant.placement),
+			(24, y),
+			24,
+			if participant.placement <= 4 {
+				WIN
+			} else {
+				LOSS
+			},
+		)?;
+		text(&root, name.unwrap_or_default(), (90, y), 18, WHITE)?;
+		text(
+			&root,
+			&format!(
+				"{} {}",
+				super::locale::label(locale, "Level"),
+				participant.level
+			),
+			(90, y + 24),
+			14,
+			MUTED,
+		)?;
+
+		// Active traits, largest first
+		let mut traits = participant
+			.traits
+			.iter()
+			.filter(|trait_| trait_.tier_current > 0)
+			.collect::<Vec<_>>();
+		traits.sort_by_key(|trait_| std::cmp::Reverse(trait_.num_units));
+		text(
+			&root,
+			&traits
+				.iter()
+				.take(5)
+				.map(|trait_| format!("{} {}", strip_set(&trait_.name), trait_.num_units))
+				.collect::<Vec<_>>()
+				.join(" · "),
+			(300, y),
+			16,
+			WHITE,
+		)?;
+
+		// Units with their star level
+		text(
+			&root,
+			&participant
+				.units
+				.iter()
+				.map(|unit| {
+					format!(
+						"{}{}",
+						strip_set(&unit.character_id),
+						"★".repeat(unit.tier.clamp(0, 3) as usize)
+					)
+				})
+				.collect::<Vec<_>>()
+				.join(" "),
+			(300, y + 24),
+			14,
+			MUTED,
+		)?;
+		y += ROW + 20;
+	}
+
+	root.present()
+}
+
+// "TFT9_Ahri" and "Set9_Sorcerer" without the set prefix
+fn strip_set(name: &str) -> &str {
+	name.split_once('_').map_or(name, |(_, name)| name)
+}
+
+fn text<'a>(
+	root: &Area<'a>,
+	text: &str,
+	position: (i32, i32),
+	size: u32,
+	color: RGBColor,
+) -> DrawResult<'a> {
+	root.draw(&Text::new(
+		text,
+		position,
+		("sans-serif", size).into_font().color(&color),
+	))
+}
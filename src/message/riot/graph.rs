@@ -0,0 +1,159 @@
+use plotters::prelude::*;
+
+use super::{riot_api::Tier, riot_db::GameResult};
+
+const WIDTH: u32 = 960;
+const HEIGHT: u32 = 540;
+// Milliseconds, time is plotted in days
+const DAY: f64 = 86_400_000.0;
+
+// Standard tiers from the bottom of the ladder, apex tiers start above them
+const TIERS: [Tier; 7] = [
+	Tier::IRON,
+	Tier::BRONZE,
+	Tier::SILVER,
+	Tier::GOLD,
+	Tier::PLATINUM,
+	Tier::DIAMOND,
+	Tier::MASTER,
+];
+
+// PNG line chart of the league points of every player over time
+pub fn generate_league_points_graph(
+	title: &str,
+	names_results: &[(&str, Vec<GameResult>)],
+) -> Result<Vec<u8>, String> {
+	super::register_fonts();
+
+	let names_points = names_results
+		.iter()
+		.map(|(name, results)| {
+			let points = super::ranked_results(results)
+				.into_iter()
+				.map(|(result, (tier, rank, points))| {
+					(
+						result.timestamp as f64 / DAY,
+						super::total_league_points(tier, rank, points),
+					)
+				})
+				.collect::<Vec<_>>();
+			(*name, points)
+		})
+		.filter(|(_, points)| !points.is_empty())
+		.collect::<Vec<_>>();
+	if names_points.is_empty() {
+		return Err(String::from("No ranked games"));
+	}
+
+	// Bounds with some margin, a single game still needs a range
+	let points = names_points.iter().flat_map(|(_, points)| points);
+	let start_time = points
+		.clone()
+		.map(|(time, _)| *time)
+		.fold(f64::MAX, f64::min);
+	let end_time = points
+		.clone()
+		.map(|(time, _)| *time)
+		.fold(f64::MIN, f64::max);
+	let end_time = end_time.max(start_time + 1.0);
+	let low = points.clone().map(|(_, points)| *points).min().unwrap() - 50;
+	let high = points.map(|(_, points)| *points).max().unwrap() + 50;
+
+	let mut buffer = vec![0; (WIDTH * HEIGHT * 3) as usize];
+	draw(
+		&mut buffer,
+		title,
+		&names_points,
+		(start_time, end_time),
+		(low, high),
+	)
+	.map_err(|err| format!("Error drawing graph: {err}"))?;
+
+	super::encode_png(&buffer, WIDTH, HEIGHT)
+}
+
+fn draw<'a>(
+	buffer: &'a mut [u8],
+	title: &str,
+	names_points: &[(&str, Vec<(f64, i32)>)],
+	(start_time, end_time): (f64, f64),
+	(low, high): (i32, i32),
+) -> Result<(), Box<dyn std::error::Error + 'a>> {
+	let root = BitMapBackend::with_buffer(buffer, (WIDTH, HEIGHT)).into_drawing_area();
+	root.fill(&WHITE)?;
+
+	let mut chart = ChartBuilder::on(&root)
+		.caption(title, ("sans-serif", 24))
+		.margin(16)
+		.x_label_area_size(32)
+		.y_label_area_size(8)
+		.build_cartesian_2d(start_time..end_time, low..high)?;
+	chart
+		.configure_mesh()
+		.disable_mesh()
+		.disable_y_axis()
+		.x_labels(6)
+		.x_label_formatter(&|time| {
+			chrono::naive::NaiveDateTime::from_timestamp_millis((*time * DAY) as i64)
+				.map_or_else(String::default, |datetime| {
+					datetime.format("%d/%m").to_string()
+				})
+		})
+		.draw()?;
+
+	// Division and tier boundaries
+	for points in (0..=TIERS.len() as i32 * 400)
+		.step_by(100)
+		.filter(|points| (low..high).contains(points))
+	{
+		let tier = usize::try_from(points / 400)
+			.ok()
+			.and_then(|index| TIERS.get(index));
+		// Apex tiers have no divisions
+		let (label, style) = match (tier, points % 400) {
+			(Some(tier), 0) if tier.is_apex() => (tier.to_string(), BLACK.mix(0.5)),
+			(Some(tier), 0) => (format!("{tier} IV"), BLACK.mix(0.5)),
+			(Some(tier), division) if !tier.is_apex() => (
+				["IV", "III", "II", "I"][division as usize / 100].to_string(),
+				BLACK.mix(0.1),
+			),
+			_ => (String::default(), BLACK.mix(0.1)),
+		};
+		chart.draw_series(LineSeries::new(
+			[(start_time, points), (end_time, points)],
+			style.stroke_width(1),
+		))?;
+		chart.draw_series([Text::new(
+			label,
+			(start_time, points),
+			("sans-serif", 14).into_font().color(&BLACK.mix(0.6)),
+		)])?;
+	}
+
+	for (index, (name, points)) in names_points.iter().enumerate() {
+		let color = Palette99::pick(index).to_rgba();
+		chart
+			.draw_series(LineSeries::new(
+				points.iter().copied(),
+				color.stroke_width(3),
+			))?
+			.label(*name)
+			.legend(move |(x, y)| PathElement::new([(x, y), (x + 20, y)], color.stroke_width(3)));
+		chart.draw_series(
+			points
+				.iter()
+				.map(|point| Circle::new(*point, 3, color.filled())),
+		)?;
+	}
+
+	chart
+		.configure_series_labels()
+		.position(SeriesLabelPosition::LowerRight)
+		.label_font(("sans-serif", 16))
+		.background_style(WHITE.mix(0.8))
+		.border_style(BLACK.mix(0.5))
+		.draw()?;
+
+	root.present()?;
+	Ok(())
+}
@@ -0,0 +1,277 @@
+use super::{
+	riot_api::{GameMode, Platform, Queue, Rank, Tier},
+	riot_config::Locale,
+};
+
+// English names of every queue, overridden by the `queues` of a locale.
+// Deprecated queues may still show up in old games.
+#[allow(deprecated)]
+const QUEUES: [(Queue, &str); 89] = [
+	(Queue::CUSTOM, "CUSTOM"),
+	(
+		Queue::SUMMONERS_RIFT_5V5_BLIND_PICK_DEPRECATED_2,
+		"NORMAL BLIND",
+	),
+	(Queue::SUMMONERS_RIFT_5V5_RANKED_SOLO_DEPRECATED_4, "RANKED"),
+	(Queue::SUMMONERS_RIFT_5V5_RANKED_PREMADE, "RANKED PREMADE"),
+	(Queue::SUMMONERS_RIFT_CO_OP_VS_AI, "CO-OP VS AI"),
+	(Queue::TWISTED_TREELINE_3V3_NORMAL, "TWISTED TREELINE"),
+	(
+		Queue::TWISTED_TREELINE_3V3_RANKED_FLEX_DEPRECATED_9,
+		"TWISTED TREELINE RANKED FLEX",
+	),
+	(
+		Queue::SUMMONERS_RIFT_5V5_DRAFT_PICK_DEPRECATED_14,
+		"NORMAL DRAFT",
+	),
+	(
+		Queue::CRYSTAL_SCAR_5V5_DOMINION_BLIND_PICK,
+		"DOMINION BLIND",
+	),
+	(
+		Queue::CRYSTAL_SCAR_5V5_DOMINION_DRAFT_PICK,
+		"DOMINION DRAFT",
+	),
+	(
+		Queue::CRYSTAL_SCAR_DOMINION_CO_OP_VS_AI,
+		"DOMINION CO-OP VS AI",
+	),
+	(
+		Queue::SUMMONERS_RIFT_CO_OP_VS_AI_INTRO_BOT_DEPRECATED_31,
+		"CO-OP VS AI INTRO",
+	),
+	(
+		Queue::SUMMONERS_RIFT_CO_OP_VS_AI_BEGINNER_BOT_DEPRECATED_32,
+		"CO-OP VS AI BEGINNER",
+	),
+	(
+		Queue::SUMMONERS_RIFT_CO_OP_VS_AI_INTERMEDIATE_BOT_DEPRECATED_33,
+		"CO-OP VS AI INTERMEDIATE",
+	),
+	(
+		Queue::TWISTED_TREELINE_3V3_RANKED_TEAM,
+		"TWISTED TREELINE RANKED TEAM",
+	),
+	(Queue::SUMMONERS_RIFT_5V5_RANKED_TEAM, "RANKED TEAM"),
+	(
+		Queue::TWISTED_TREELINE_CO_OP_VS_AI,
+		"TWISTED TREELINE CO-OP VS AI",
+	),
+	(Queue::SUMMONERS_RIFT_5V5_TEAM_BUILDER, "TEAM BUILDER"),
+	(Queue::HOWLING_ABYSS_5V5_ARAM_DEPRECATED_65, "ARAM"),
+	(Queue::HOWLING_ABYSS_ARAM_CO_OP_VS_AI, "ARAM CO-OP VS AI"),
+	(
+		Queue::SUMMONERS_RIFT_ONE_FOR_ALL_DEPRECATED_70,
+		"ONE FOR ALL",
+	),
+	(
+		Queue::HOWLING_ABYSS_1V1_SNOWDOWN_SHOWDOWN,
+		"SNOWDOWN SHOWDOWN 1V1",
+	),
+	(
+		Queue::HOWLING_ABYSS_2V2_SNOWDOWN_SHOWDOWN,
+		"SNOWDOWN SHOWDOWN 2V2",
+	),
+	(Queue::SUMMONERS_RIFT_6V6_HEXAKILL, "HEXAKILL"),
+	(Queue::SUMMONERS_RIFT_ULTRA_RAPID_FIRE, "URF"),
+	(
+		Queue::HOWLING_ABYSS_ONE_FOR_ALL_MIRROR_MODE,
+		"ONE FOR ALL MIRROR",
+	),
+	(
+		Queue::SUMMONERS_RIFT_CO_OP_VS_AI_ULTRA_RAPID_FIRE,
+		"URF CO-OP VS AI",
+	),
+	(Queue::SUMMONERS_RIFT_DOOM_BOTS_RANK_1, "DOOM BOTS"),
+	(Queue::SUMMONERS_RIFT_DOOM_BOTS_RANK_2, "DOOM BOTS"),
+	(Queue::SUMMONERS_RIFT_DOOM_BOTS_RANK_5, "DOOM BOTS"),
+	(Queue::CRYSTAL_SCAR_ASCENSION_DEPRECATED_96, "ASCENSION"),
+	(Queue::TWISTED_TREELINE_6V6_HEXAKILL, "HEXAKILL"),
+	(Queue::BUTCHERS_BRIDGE_5V5_ARAM, "ARAM BUTCHER'S BRIDGE"),
+	(
+		Queue::HOWLING_ABYSS_LEGEND_OF_THE_PORO_KING_DEPRECATED_300,
+		"LEGEND OF THE PORO KING",
+	),
+	(Queue::SUMMONERS_RIFT_NEMESIS, "NEMESIS"),
+	(
+		Queue::SUMMONERS_RIFT_BLACK_MARKET_BRAWLERS,
+		"BLACK MARKET BRAWLERS",
+	),
+	(
+		Queue::SUMMONERS_RIFT_NEXUS_SIEGE_DEPRECATED_315,
+		"NEXUS SIEGE",
+	),
+	(
+		Queue::CRYSTAL_SCAR_DEFINITELY_NOT_DOMINION,
+		"DEFINITELY NOT DOMINION",
+	),
+	(Queue::SUMMONERS_RIFT_ARURF_DEPRECATED_318, "ARURF"),
+	(Queue::SUMMONERS_RIFT_ALL_RANDOM, "ALL RANDOM"),
+	(Queue::SUMMONERS_RIFT_5V5_DRAFT_PICK, "NORMAL DRAFT"),
+	(Queue::SUMMONERS_RIFT_5V5_RANKED_DYNAMIC, "RANKED DYNAMIC"),
+	(Queue::SUMMONERS_RIFT_5V5_RANKED_SOLO, "RANKED"),
+	(Queue::SUMMONERS_RIFT_5V5_BLIND_PICK, "NORMAL BLIND"),
+	(Queue::SUMMONERS_RIFT_5V5_RANKED_FLEX, "RANKED FLEX"),
+	(Queue::HOWLING_ABYSS_5V5_ARAM, "ARAM"),
+	(
+		Queue::TWISTED_TREELINE_3V3_BLIND_PICK,
+		"TWISTED TREELINE BLIND",
+	),
+	(
+		Queue::TWISTED_TREELINE_3V3_RANKED_FLEX_DEPRECATED_470,
+		"TWISTED TREELINE RANKED FLEX",
+	),
+	(
+		Queue::SUMMONERS_RIFT_BLOOD_HUNT_ASSASSIN,
+		"BLOOD HUNT ASSASSIN",
+	),
+	(Queue::COSMIC_RUINS_DARK_STAR_SINGULARITY, "DARK STAR"),
+	(Queue::SUMMONERS_RIFT_CLASH, "CLASH"),
+	(
+		Queue::TWISTED_TREELINE_CO_OP_VS_AI_INTERMEDIATE_BOT,
+		"TWISTED TREELINE CO-OP VS AI INTERMEDIATE",
+	),
+	(
+		Queue::TWISTED_TREELINE_CO_OP_VS_AI_INTRO_BOT,
+		"TWISTED TREELINE CO-OP VS AI INTRO",
+	),
+	(
+		Queue::TWISTED_TREELINE_CO_OP_VS_AI_BEGINNER_BOT,
+		"TWISTED TREELINE CO-OP VS AI BEGINNER",
+	),
+	(
+		Queue::SUMMONERS_RIFT_CO_OP_VS_AI_INTRO_BOT,
+		"CO-OP VS AI INTRO",
+	),
+	(
+		Queue::SUMMONERS_RIFT_CO_OP_VS_AI_BEGINNER_BOT,
+		"CO-OP VS AI BEGINNER",
+	),
+	(
+		Queue::SUMMONERS_RIFT_CO_OP_VS_AI_INTERMEDIATE_BOT,
+		"CO-OP VS AI INTERMEDIATE",
+	),
+	(Queue::SUMMONERS_RIFT_ARURF, "ARURF"),
+	(Queue::CRYSTAL_SCAR_ASCENSION, "ASCENSION"),
+	(
+		Queue::HOWLING_ABYSS_LEGEND_OF_THE_PORO_KING,
+		"LEGEND OF THE PORO KING",
+	),
+	(Queue::SUMMONERS_RIFT_NEXUS_SIEGE, "NEXUS SIEGE"),
+	(Queue::SUMMONERS_RIFT_DOOM_BOTS_VOTING, "DOOM BOTS VOTING"),
+	(Queue::SUMMONERS_RIFT_DOOM_BOTS_STANDARD, "DOOM BOTS"),
+	(
+		Queue::VALORAN_CITY_PARK_STAR_GUARDIAN_INVASION_NORMAL,
+		"STAR GUARDIAN INVASION",
+	),
+	(
+		Queue::VALORAN_CITY_PARK_STAR_GUARDIAN_INVASION_ONSLAUGHT,
+		"STAR GUARDIAN INVASION ONSLAUGHT",
+	),
+	(Queue::OVERCHARGE_PROJECT_HUNTERS, "PROJECT: HUNTERS"),
+	(Queue::SUMMONERS_RIFT_SNOW_ARURF, "SNOW ARURF"),
+	(Queue::SUMMONERS_RIFT_ONE_FOR_ALL, "ONE FOR ALL"),
+	(Queue::CRASH_SITE_ODYSSEY_EXTRACTION_INTRO, "ODYSSEY INTRO"),
+	(Queue::CRASH_SITE_ODYSSEY_EXTRACTION_CADET, "ODYSSEY CADET"),
+	(
+		Queue::CRASH_SITE_ODYSSEY_EXTRACTION_CREWMEMBER,
+		"ODYSSEY CREWMEMBER",
+	),
+	(
+		Queue::CRASH_SITE_ODYSSEY_EXTRACTION_CAPTAIN,
+		"ODYSSEY CAPTAIN",
+	),
+	(
+		Queue::CRASH_SITE_ODYSSEY_EXTRACTION_ONSLAUGHT,
+		"ODYSSEY ONSLAUGHT",
+	),
+	(Queue::CONVERGENCE_TEAMFIGHT_TACTICS, "NORMAL"),
+	(Queue::CONVERGENCE_TEAMFIGHT_TACTICS_1V0, "1V0"),
+	(Queue::CONVERGENCE_TEAMFIGHT_TACTICS_2V0, "2V0"),
+	(Queue::CONVERGENCE_RANKED_TEAMFIGHT_TACTICS, "RANKED"),
+	(Queue::CONVERGENCE_TEAMFIGHT_TACTICS_TUTORIAL, "TUTORIAL"),
+	(
+		Queue::CONVERGENCE_TEAMFIGHT_TACTICS_SIMLUATION,
+		"SIMULATION",
+	),
+	(
+		Queue::CONVERGENCE_RANKED_TEAMFIGHT_TACTICS_HYPER_ROLL_,
+		"HYPER ROLL",
+	),
+	(
+		Queue::CONVERGENCE_RANKED_TEAMFIGHT_TACTICS_DOUBLE_UP_WORKSHOP__DEPRECATED_1150,
+		"DOUBLE UP",
+	),
+	(
+		Queue::CONVERGENCE_RANKED_TEAMFIGHT_TACTICS_DOUBLE_UP_WORKSHOP_,
+		"DOUBLE UP",
+	),
+	(
+		Queue::NEXUS_BLITZ_NEXUS_BLITZ_DEPRECATED_1200,
+		"NEXUS BLITZ",
+	),
+	(Queue::NEXUS_BLITZ_NEXUS_BLITZ, "NEXUS BLITZ"),
+	(
+		Queue::SUMMONERS_RIFT_ULTIMATE_SPELLBOOK,
+		"ULTIMATE SPELLBOOK",
+	),
+	(Queue::SUMMONERS_RIFT_PICK_URF, "URF"),
+	(Queue::SUMMONERS_RIFT_TUTORIAL_1, "TUTORIAL 1"),
+	(Queue::SUMMONERS_RIFT_TUTORIAL_2, "TUTORIAL 2"),
+	(Queue::SUMMONERS_RIFT_TUTORIAL_3, "TUTORIAL 3"),
+];
+
+// Name of a queue, none if it is not in the catalog
+pub fn queue(locale: &Locale, queue: Queue) -> Option<String> {
+	locale.queues.get(&queue.0).cloned().or_else(|| {
+		QUEUES
+			.iter()
+			.find(|(known, _)| *known == queue)
+			.map(|(_, name)| String::from(*name))
+	})
+}
+
+// Name of a game mode, Summoner's Rift (CLASSIC) being normal games by default
+pub fn mode(locale: &Locale, mode: &GameMode) -> String {
+	let mode = mode.to_string();
+	locale
+		.modes
+		.get(&mode)
+		.cloned()
+		.unwrap_or_else(|| match mode.as_str() {
+			"CLASSIC" => String::from("NORMAL"),
+			_ => mode,
+		})
+}
+
+pub fn tier(locale: &Locale, tier: Tier) -> String {
+	let tier = tier.as_ref();
+	locale
+		.tiers
+		.get(tier)
+		.cloned()
+		.unwrap_or_else(|| String::from(tier))
+}
+
+pub fn division(locale: &Locale, division: Rank) -> String {
+	let division = division.to_string();
+	locale.divisions.get(&division).cloned().unwrap_or(division)
+}
+
+pub fn region(locale: &Locale, platform: Platform) -> String {
+	let region = platform.as_region_str();
+	locale
+		.regions
+		.get(region)
+		.cloned()
+		.unwrap_or_else(|| String::from(region))
+}
+
+// Text of image cards and achievements, e.g. "VICTORY"
+pub fn label(locale: &Locale, label: &str) -> String {
+	locale
+		.labels
+		.get(label)
+		.cloned()
+		.unwrap_or_else(|| String::from(label))
+}
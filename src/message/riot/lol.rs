@@ -1,171 +1,398 @@
 use super::{
+	achievement,
+	placeholders::{self, Kind},
 	riot_api::{
 		lol::{Game, League, Participant, Player},
 		GameMode, Platform, Queue, Tier,
 	},
-	riot_config::lol::{Message, MessageTemplate},
+	riot_config::{
+		lol::{Message, MessageTemplate, StreakTemplate},
+		Locale,
+	},
 };
 
 pub fn generate_messages(
 	game: &Game,
 	platform: Platform,
-	players_participants_leagues: &[(Player, Participant, Option<League>)],
+	players_participants_leagues_streaks: &[(Player, Participant, Option<League>, i32)],
 	message: &Message,
+	locale: &Locale,
 ) -> Vec<String> {
-	[false, true]
-		.into_iter()
-		.filter_map(|result| {
-			let players_participants_leagues = players_participants_leagues
-				.iter()
-				.filter(|(_, participant, ..)| participant.win == result)
-				.collect::<Vec<_>>();
-			match players_participants_leagues.len() {
-				0 => None,
-				1 => Some(generate_message_single(
-					game,
-					platform,
-					result,
-					players_participants_leagues.first().unwrap(),
-					&message.single,
-				)),
-				2..=5 => Some(generate_message_multiple(
-					game,
-					platform,
-					result,
-					&players_participants_leagues,
-					&message.multiple,
-				)),
-				n => {
-					tracing::warn!(
+	let messages = [false, true].into_iter().filter_map(|result| {
+		let players_participants_leagues_streaks = players_participants_leagues_streaks
+			.iter()
+			.filter(|(_, participant, ..)| participant.win == result)
+			.collect::<Vec<_>>();
+		match players_participants_leagues_streaks.len() {
+			0 => None,
+			1 => Some(generate_message_single(
+				game,
+				platform,
+				result,
+				players_participants_leagues_streaks.first().unwrap(),
+				&message.single,
+				locale,
+			)),
+			2..=5 => Some(generate_message_multiple(
+				game,
+				platform,
+				result,
+				&players_participants_leagues_streaks,
+				&message.multiple,
+				locale,
+			)),
+			n => {
+				tracing::warn!(
 						game = game.metadata.match_id,
 						"Trying to generate a message from a RIOT LOL game with {n} tracked participants"
 					);
-					None
-				}
+				None
 			}
-		})
+		}
+	});
+	messages
+		.chain(generate_streak_messages(
+			game,
+			platform,
+			players_participants_leagues_streaks,
+			&message.streak,
+			locale,
+		))
 		.collect()
 }
 
+// Every template rendered with example values
+pub fn generate_samples(message: &Message) -> Vec<(String, String)> {
+	let sample = |template: &str| placeholders::sample(template, "LOL");
+	let Message {
+		single,
+		multiple,
+		streak,
+		achievement,
+	} = message;
+	let samples = vec![
+		("single.win-single", sample(&single.win_single)),
+		(
+			"single.win-single-ranked",
+			sample(&single.win_single_ranked),
+		),
+		("single.loss-single", sample(&single.loss_single)),
+		(
+			"single.loss-single-ranked",
+			sample(&single.loss_single_ranked),
+		),
+		(
+			"multiple.win-multiple",
+			sample(&placeholders::replace(
+				&multiple.win_multiple,
+				&[(
+					"singles",
+					multiple.win_single_ranked.clone() + &multiple.win_single,
+				)],
+			)),
+		),
+		(
+			"multiple.loss-multiple",
+			sample(&placeholders::replace(
+				&multiple.loss_multiple,
+				&[(
+					"singles",
+					multiple.loss_single_ranked.clone() + &multiple.loss_single,
+				)],
+			)),
+		),
+	];
+	let streaks = [("win", &streak.win), ("loss", &streak.loss)]
+		.into_iter()
+		.flat_map(|(result, templates)| {
+			templates.iter().map(move |(games, template)| {
+				(format!("streak.{result}.{games}"), sample(template))
+			})
+		});
+	samples
+		.into_iter()
+		.map(|(template, sample)| (String::from(template), sample))
+		.chain(streaks)
+		.chain(
+			(!achievement.is_empty()).then(|| (String::from("achievement"), sample(achievement))),
+		)
+		.collect()
+}
+
+// Problems of every template by path, see `placeholders::validate`
+pub fn validate(message: &Message) -> Vec<(String, String)> {
+	let Message {
+		single,
+		multiple,
+		streak,
+		achievement,
+	} = message;
+	let mut paths_templates = vec![
+		("single.win-single", &single.win_single, true, Kind::Single),
+		(
+			"single.win-single-ranked",
+			&single.win_single_ranked,
+			true,
+			Kind::Single,
+		),
+		(
+			"single.loss-single",
+			&single.loss_single,
+			true,
+			Kind::Single,
+		),
+		(
+			"single.loss-single-ranked",
+			&single.loss_single_ranked,
+			true,
+			Kind::Single,
+		),
+		(
+			"multiple.win-multiple",
+			&multiple.win_multiple,
+			true,
+			Kind::Multiple,
+		),
+		(
+			"multiple.win-single",
+			&multiple.win_single,
+			false,
+			Kind::Single,
+		),
+		(
+			"multiple.win-single-ranked",
+			&multiple.win_single_ranked,
+			false,
+			Kind::Single,
+		),
+		(
+			"multiple.loss-multiple",
+			&multiple.loss_multiple,
+			true,
+			Kind::Multiple,
+		),
+		(
+			"multiple.loss-single",
+			&multiple.loss_single,
+			false,
+			Kind::Single,
+		),
+		(
+			"multiple.loss-single-ranked",
+			&multiple.loss_single_ranked,
+			false,
+			Kind::Single,
+		),
+		("achievement", achievement, false, Kind::Achievement),
+	]
+	.into_iter()
+	.map(|(path, template, required, kind)| (String::from(path), template.as_str(), required, kind))
+	.collect::<Vec<_>>();
+	for (result, templates) in [("win", &streak.win), ("loss", &streak.loss)] {
+		for (games, template) in templates {
+			paths_templates.push((
+				format!("streak.{result}.{games}"),
+				template,
+				true,
+				Kind::Streak,
+			));
+		}
+	}
+	placeholders::validate("LOL", paths_templates)
+}
+
 fn generate_message_single(
 	game: &Game,
 	platform: Platform,
 	result: bool,
-	(player, participant, league): &(Player, Participant, Option<League>),
+	(player, participant, league, streak): &(Player, Participant, Option<League>, i32),
 	message_template: &MessageTemplate,
+	locale: &Locale,
 ) -> String {
-	substitute_common(
-		game,
-		platform,
-		result,
-		match (result, league) {
-			(true, None) => &message_template.win_single,
-			(true, Some(_)) => &message_template.win_single_ranked,
-			(false, None) => &message_template.loss_single,
-			(false, Some(_)) => &message_template.loss_single_ranked,
-		},
-	)
-	.replace("{sumoner_name}", &player.name)
-	.replace("{champion}", &participant.champion_name)
-	.replace("{kills}", &format!("{}", participant.kills))
-	.replace("{deaths}", &format!("{}", participant.deaths))
-	.replace("{assists}", &format!("{}", participant.assists))
-	.replace(
-		"{damage}",
-		&format!("{}", participant.total_damage_dealt_to_champions),
-	)
-	.replace(
-		"{damage_percentage}",
-		&format!("{:.1}", get_damage_percentage(game, result, participant)),
-	)
-	.replace(
-		"{tier}",
-		league
-			.as_ref()
-			.and_then(|league| league.tier)
-			.unwrap_or(Tier::UNRANKED)
-			.as_ref(),
-	)
-	.replace(
-		"{rank}",
-		&league
-			.as_ref()
-			.and_then(|league| league.rank)
-			.map_or_else(String::default, |rank| rank.to_string()),
-	)
-	.replace(
-		"{lp}",
-		&format!(
-			"{}",
-			league.as_ref().map_or(-1, |league| league.league_points)
+	placeholders::replace(
+		&substitute_common(
+			game,
+			platform,
+			match (result, league) {
+				(true, None) => &message_template.win_single,
+				(true, Some(_)) => &message_template.win_single_ranked,
+				(false, None) => &message_template.loss_single,
+				(false, Some(_)) => &message_template.loss_single_ranked,
+			},
+			locale,
 		),
+		&[
+			("summoner_name", player.name.clone()),
+			("champion", participant.champion_name.clone()),
+			("kills", format!("{}", participant.kills)),
+			("deaths", format!("{}", participant.deaths)),
+			("assists", format!("{}", participant.assists)),
+			(
+				"damage",
+				format!("{}", participant.total_damage_dealt_to_champions),
+			),
+			(
+				"damage_percentage",
+				format!("{:.1}", get_damage_percentage(game, result, participant)),
+			),
+			(
+				"tier",
+				super::locale::tier(
+					locale,
+					league
+						.as_ref()
+						.and_then(|league| league.tier)
+						.unwrap_or(Tier::UNRANKED),
+				),
+			),
+			(
+				"rank",
+				league
+					.as_ref()
+					.and_then(|league| league.rank)
+					.map_or_else(String::default, |rank| {
+						super::locale::division(locale, rank)
+					}),
+			),
+			(
+				"lp",
+				format!(
+					"{}",
+					league.as_ref().map_or(-1, |league| league.league_points)
+				),
+			),
+			("streak", format!("{}", streak.abs())),
+		],
 	)
 }
 
+// Announcements of the achievements unlocked by a player in a game
+pub fn generate_achievement_messages(
+	game: &Game,
+	platform: Platform,
+	player: &Player,
+	achievements: &[String],
+	message: &Message,
+	locale: &Locale,
+) -> Vec<String> {
+	if message.achievement.is_empty() {
+		return Vec::default();
+	}
+	achievements
+		.iter()
+		.map(|achievement| {
+			placeholders::replace(
+				&substitute_common(game, platform, &message.achievement, locale),
+				&[
+					("summoner_name", player.name.clone()),
+					("achievement", achievement::describe(achievement, locale)),
+				],
+			)
+		})
+		.collect()
+}
+
+// Messages of the players whose streak has just reached a configured length
+fn generate_streak_messages(
+	game: &Game,
+	platform: Platform,
+	players_participants_leagues_streaks: &[(Player, Participant, Option<League>, i32)],
+	streak_template: &StreakTemplate,
+	locale: &Locale,
+) -> Vec<String> {
+	players_participants_leagues_streaks
+		.iter()
+		.filter_map(|(player, .., streak)| {
+			let templates = match *streak > 0 {
+				true => &streak_template.win,
+				false => &streak_template.loss,
+			};
+			let template = templates.get(&streak.unsigned_abs())?;
+			Some(placeholders::replace(
+				&substitute_common(game, platform, template, locale),
+				&[
+					("summoner_name", player.name.clone()),
+					("streak", format!("{}", streak.abs())),
+				],
+			))
+		})
+		.collect()
+}
+
 fn generate_message_multiple(
 	game: &Game,
 	platform: Platform,
 	result: bool,
-	players_participants_leagues: &[&(Player, Participant, Option<League>)],
+	players_participants_leagues_streaks: &[&(Player, Participant, Option<League>, i32)],
 	message_template: &MessageTemplate,
+	locale: &Locale,
 ) -> String {
-	substitute_common(
-		game,
-		platform,
-		result,
-		if result {
-			&message_template.win_multiple
-		} else {
-			&message_template.loss_multiple
-		},
-	)
-	.replace("{sumoner_names}", &{
-		// TODO: interperse interperse // iter_intersperse #79524 // https://github.com/rust-lang/rust/issues/79524
-		let mut player_names = players_participants_leagues
-			.iter()
-			.map(|(player, ..)| &player.name);
-		let first = player_names.next().unwrap();
-		player_names.fold(String::from(first), |mut player_names, player_name| {
-			player_names.push_str(" &amp; ");
-			player_names.push_str(player_name);
-			player_names
-		})
-	})
-	.replace(
-		"{singles}",
-		&players_participants_leagues
-			.iter()
-			.map(|player_participant_league| {
-				generate_message_single(
-					game,
-					platform,
-					result,
-					player_participant_league,
-					message_template,
-				)
-			})
-			.collect::<String>(),
+	placeholders::replace(
+		&substitute_common(
+			game,
+			platform,
+			if result {
+				&message_template.win_multiple
+			} else {
+				&message_template.loss_multiple
+			},
+			locale,
+		),
+		&[
+			("summoner_names", {
+				// TODO: interperse interperse // iter_intersperse #79524 // https://github.com/rust-lang/rust/issues/79524
+				let mut player_names = players_participants_leagues_streaks
+					.iter()
+					.map(|(player, ..)| &player.name);
+				let first = player_names.next().unwrap();
+				player_names.fold(String::from(first), |mut player_names, player_name| {
+					player_names.push_str(" &amp; ");
+					player_names.push_str(player_name);
+					player_names
+				})
+			}),
+			(
+				"singles",
+				players_participants_leagues_streaks
+					.iter()
+					.map(|player_participant_league| {
+						generate_message_single(
+							game,
+							platform,
+							result,
+							player_participant_league,
+							message_template,
+							locale,
+						)
+					})
+					.collect::<String>(),
+			),
+		],
 	)
 }
 
-fn substitute_common(game: &Game, platform: Platform, _result: bool, message: &str) -> String {
-	message
-		.replace("{mode}", &get_queue_or_mode_string(game))
-		.replace(
-			"{game_duration_min}",
-			&format!("{}", game.info.game_duration / 60),
-		)
-		.replace("{region}", platform.as_region_str())
+fn substitute_common(game: &Game, platform: Platform, message: &str, locale: &Locale) -> String {
+	placeholders::replace(
+		message,
+		&[
+			("mode", get_queue_or_mode_string(game, locale)),
+			(
+				"game_duration_min",
+				format!("{}", game.info.game_duration / 60),
+			),
+			("region", super::locale::region(locale, platform)),
+		],
+	)
 }
 
-fn get_queue_or_mode_string(game: &Game) -> String {
+// Custom games of modes other than Summoner's Rift are named after the mode
+pub(super) fn get_queue_or_mode_string(game: &Game, locale: &Locale) -> String {
 	match (&game.info.game_mode, game.info.queue_id) {
-		(GameMode::CLASSIC, Queue::SUMMONERS_RIFT_5V5_RANKED_SOLO) => String::from("RANKED"),
-		(GameMode::CLASSIC, Queue::SUMMONERS_RIFT_5V5_RANKED_FLEX) => String::from("RANKED FLEX"),
-		(GameMode::CLASSIC, Queue::CUSTOM) => String::from("CUSTOM"),
-		(GameMode::CLASSIC, ..) => String::from("NORMAL"),
-		(mode, ..) => mode.to_string(),
+		(GameMode::CLASSIC, queue) => super::locale::queue(locale, queue),
+		(_, Queue::CUSTOM) => None,
+		(_, queue) => super::locale::queue(locale, queue),
 	}
+	.unwrap_or_else(|| super::locale::mode(locale, &game.info.game_mode))
 }
 
 fn get_damage_percentage(game: &Game, result: bool, participant: &Participant) -> f64 {
@@ -0,0 +1,354 @@
+// Every placeholder of the message templates, written `{name}` or `{alias}`
+pub struct Placeholder {
+	pub name: &'static str,
+	// Other accepted spellings, e.g. the historical `sumoner_name`
+	pub aliases: &'static [&'static str],
+	pub description: &'static str,
+	// "LOL", "TFT" or both
+	pub products: &'static [&'static str],
+	// Templates replacing it
+	pub kinds: &'static [Kind],
+	pub example: &'static str,
+}
+
+impl Placeholder {
+	// Name and aliases
+	pub fn spellings(&self) -> impl Iterator<Item = &'static str> {
+		std::iter::once(self.name).chain(self.aliases.iter().copied())
+	}
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+	// `single` templates, and the single templates of `multiple` and `duo`
+	Single,
+	// `win-multiple` and `loss-multiple` of `multiple`, `top-duo` and
+	// `bottom-duo` of `duo`
+	Multiple,
+	Streak,
+	Achievement,
+}
+
+impl Kind {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Kind::Single => "single",
+			Kind::Multiple => "multiple/duo",
+			Kind::Streak => "streak",
+			Kind::Achievement => "achievement",
+		}
+	}
+}
+
+const BOTH: &[&str] = &["LOL", "TFT"];
+const LOL: &[&str] = &["LOL"];
+const TFT: &[&str] = &["TFT"];
+const ALL: &[Kind] = &[
+	Kind::Single,
+	Kind::Multiple,
+	Kind::Streak,
+	Kind::Achievement,
+];
+const SINGLE: &[Kind] = &[Kind::Single];
+
+pub const PLACEHOLDERS: [Placeholder; 18] = [
+	Placeholder {
+		name: "mode",
+		aliases: &[],
+		description: "Queue, or game mode of custom games",
+		products: BOTH,
+		kinds: ALL,
+		example: "RANKED",
+	},
+	Placeholder {
+		name: "top",
+		aliases: &[],
+		description: "Placement of the player, or of the team in Double Up",
+		products: TFT,
+		kinds: ALL,
+		example: "2",
+	},
+	Placeholder {
+		name: "game_duration_min",
+		aliases: &[],
+		description: "Game duration in minutes",
+		products: BOTH,
+		kinds: ALL,
+		example: "31",
+	},
+	Placeholder {
+		name: "region",
+		aliases: &[],
+		description: "Region of the platform",
+		products: BOTH,
+		kinds: ALL,
+		example: "EUW",
+	},
+	Placeholder {
+		name: "summoner_name",
+		aliases: &["sumoner_name"],
+		description: "Summoner name of the player",
+		products: BOTH,
+		kinds: &[Kind::Single, Kind::Streak, Kind::Achievement],
+		example: "Faker",
+	},
+	Placeholder {
+		name: "summoner_names",
+		aliases: &["sumoner_names"],
+		description: "Summoner names of the players, joined by &",
+		products: BOTH,
+		kinds: &[Kind::Multiple],
+		example: "Faker &amp; Caps",
+	},
+	Placeholder {
+		name: "singles",
+		aliases: &[],
+		description: "Single template of every player, from `multiple` or `duo`",
+		products: BOTH,
+		kinds: &[Kind::Multiple],
+		example: "- <b>Faker</b>\n- <b>Caps</b>\n",
+	},
+	Placeholder {
+		name: "champion",
+		aliases: &[],
+		description: "Champion played",
+		products: LOL,
+		kinds: SINGLE,
+		example: "Ahri",
+	},
+	Placeholder {
+		name: "kills",
+		aliases: &[],
+		description: "Kills",
+		products: LOL,
+		kinds: SINGLE,
+		example: "10",
+	},
+	Placeholder {
+		name: "deaths",
+		aliases: &[],
+		description: "Deaths",
+		products: LOL,
+		kinds: SINGLE,
+		example: "2",
+	},
+	Placeholder {
+		name: "assists",
+		aliases: &[],
+		description: "Assists",
+		products: LOL,
+		kinds: SINGLE,
+		example: "8",
+	},
+	Placeholder {
+		name: "damage",
+		aliases: &[],
+		description: "Damage dealt to champions",
+		products: LOL,
+		kinds: SINGLE,
+		example: "25432",
+	},
+	Placeholder {
+		name: "damage_percentage",
+		aliases: &[],
+		description: "Share of the damage to champions of the team, in percent",
+		products: LOL,
+		kinds: SINGLE,
+		example: "31.4",
+	},
+	Placeholder {
+		name: "tier",
+		aliases: &[],
+		description: "Tier after the game, in ranked templates",
+		products: BOTH,
+		kinds: SINGLE,
+		example: "GOLD",
+	},
+	Placeholder {
+		name: "rank",
+		aliases: &[],
+		description: "Division after the game, in ranked templates",
+		products: BOTH,
+		kinds: SINGLE,
+		example: "II",
+	},
+	Placeholder {
+		name: "lp",
+		aliases: &[],
+		description: "League points after the game, in ranked templates",
+		products: BOTH,
+		kinds: SINGLE,
+		example: "42",
+	},
+	Placeholder {
+		name: "streak",
+		aliases: &[],
+		description: "Games of the current win or loss (top or bottom 4) streak in the queue",
+		products: BOTH,
+		kinds: &[Kind::Single, Kind::Streak],
+		example: "3",
+	},
+	Placeholder {
+		name: "achievement",
+		aliases: &[],
+		description: "Achievement unlocked",
+		products: BOTH,
+		kinds: &[Kind::Achievement],
+		example: "Reached GOLD",
+	},
+];
+
+// Placeholders of a kind of template of a product
+pub fn placeholders(product: &str, kind: Kind) -> impl Iterator<Item = &'static Placeholder> + '_ {
+	PLACEHOLDERS.iter().filter(move |placeholder| {
+		placeholder.products.contains(&product) && placeholder.kinds.contains(&kind)
+	})
+}
+
+// Template with the placeholders named in `names_values` replaced, by any
+// spelling
+pub fn replace(template: &str, names_values: &[(&str, String)]) -> String {
+	names_values
+		.iter()
+		.fold(String::from(template), |message, (name, value)| {
+			let placeholder = PLACEHOLDERS
+				.iter()
+				.find(|placeholder| placeholder.name == *name)
+				.unwrap_or_else(|| panic!("Unknown placeholder {name}"));
+			placeholder.spellings().fold(message, |message, spelling| {
+				message.replace(&format!("{{{spelling}}}"), value)
+			})
+		})
+}
+
+// Template with every placeholder of a product replaced by its example
+pub fn sample(template: &str, product: &str) -> String {
+	replace(
+		template,
+		&PLACEHOLDERS
+			.iter()
+			.filter(|placeholder| placeholder.products.contains(&product))
+			.map(|placeholder| (placeholder.name, String::from(placeholder.example)))
+			.collect::<Vec<_>>(),
+	)
+}
+
+// Problems of templates of a product by path: empty when `required`, or with
+// placeholders that their kind does not replace
+pub(super) fn validate(
+	product: &str,
+	paths_templates: Vec<(String, &str, bool, Kind)>,
+) -> Vec<(String, String)> {
+	let mut paths_problems = Vec::default();
+	for (path, template, required, kind) in paths_templates {
+		if required && template.trim().is_empty() {
+			paths_problems.push((path.clone(), String::from("empty template")));
+		}
+		for placeholder in find(template) {
+			let name = &placeholder[1..placeholder.len() - 1];
+			// Closest placeholder by any spelling, suggested by its name
+			let (closest, distance) = placeholders(product, kind)
+				.flat_map(|placeholder| {
+					placeholder
+						.spellings()
+						.map(|spelling| (placeholder.name, edit_distance(spelling, name)))
+				})
+				.min_by_key(|(_, distance)| *distance)
+				.unwrap_or(("", usize::MAX));
+			let problem = match distance {
+				0 => continue,
+				1..=2 => {
+					format!("unknown placeholder {placeholder}, did you mean {{{closest}}}?")
+				}
+				_ => format!(
+					"unknown placeholder {placeholder}, expected one of {}",
+					placeholders(product, kind)
+						.map(|placeholder| format!("{{{}}}", placeholder.name))
+						.collect::<Vec<_>>()
+						.join(" ")
+				),
+			};
+			paths_problems.push((path.clone(), problem));
+		}
+	}
+	paths_problems
+}
+
+// Every `{name}` of a template
+fn find(template: &str) -> Vec<&str> {
+	let mut placeholders = Vec::default();
+	let mut rest = template;
+	while let Some(start) = rest.find('{') {
+		rest = &rest[start..];
+		match rest[1..].find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')) {
+			Some(end) if end > 0 && rest[1 + end..].starts_with('}') => {
+				placeholders.push(&rest[..end + 2]);
+				rest = &rest[end + 2..];
+			}
+			_ => rest = &rest[1..],
+		}
+	}
+	placeholders
+}
+
+// Levenshtein distance, to suggest misspelled placeholders
+fn edit_distance(a: &str, b: &str) -> usize {
+	let b = b.chars().collect::<Vec<_>>();
+	let mut distances = (0..=b.len()).collect::<Vec<_>>();
+	for (i, a) in a.chars().enumerate() {
+		let mut diagonal = distances[0];
+		distances[0] = i + 1;
+		for (j, b) in b.iter().enumerate() {
+			let substitution = diagonal + usize::from(a != *b);
+			diagonal = distances[j + 1];
+			distances[j + 1] = substitution.min(distances[j] + 1).min(distances[j + 1] + 1);
+		}
+	}
+	distances[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn spellings() {
+		assert_eq!(
+			replace(
+				"{summoner_name} {sumoner_name} {riot_id} {summoner_names} {top}",
+				&[
+					("summoner_name", String::from("Faker")),
+					("summoner_names", String::from("Faker &amp; Caps")),
+				]
+			),
+			"Faker Faker {riot_id} Faker &amp; Caps {top}"
+		);
+		assert_eq!(
+			sample("{sumoner_name} {champion} {top}", "TFT"),
+			"Faker {champion} 2"
+		);
+		assert_eq!(
+			validate(
+				"LOL",
+				vec![(
+					String::from("streak"),
+					"{sumoner_name} {streak} {kills} {sumoner_nam}",
+					true,
+					Kind::Streak
+				)]
+			),
+			[
+				(
+					String::from("streak"),
+					String::from(
+						"unknown placeholder {kills}, expected one of {mode} {game_duration_min} {region} {summoner_name} {streak}"
+					)
+				),
+				(
+					String::from("streak"),
+					String::from("unknown placeholder {sumoner_nam}, did you mean {summoner_name}?")
+				)
+			]
+		);
+	}
+}
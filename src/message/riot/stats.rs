@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+
+use super::{
+	riot_api::{Rank, Tier},
+	riot_db::GameResult,
+};
+
+pub fn generate_lol(name: &str, period: &str, results: &[GameResult]) -> String {
+	if results.is_empty() {
+		return generate_empty(name, "LOL", period);
+	}
+
+	let wins = results.iter().filter(|result| result.result > 0).count();
+	let mut lines = vec![
+		format!("📊 <b>{}</b> · LOL · {period}", super::escape(name)),
+		format!(
+			"Games: {} · Win rate: {:.1}%",
+			results.len(),
+			percentage(wins, results.len())
+		),
+	];
+	lines.extend(generate_league_trend(results));
+
+	let mut champions = BTreeMap::<&str, usize>::default();
+	for champion in results
+		.iter()
+		.filter_map(|result| result.champion.as_deref())
+	{
+		*champions.entry(champion).or_default() += 1;
+	}
+	let mut champions = Vec::from_iter(champions);
+	champions.sort_by(|(_, a), (_, b)| b.cmp(a));
+	if !champions.is_empty() {
+		lines.push(format!(
+			"Champions: {}",
+			champions
+				.iter()
+				.take(3)
+				.map(|(champion, games)| format!("{champion} ({games})"))
+				.collect::<Vec<_>>()
+				.join(", ")
+		));
+	}
+
+	// Best and worst games by KDA ratio
+	let kda = |(kills, deaths, assists): (i32, i32, i32)| {
+		f64::from(kills + assists) / f64::from(deaths.max(1))
+	};
+	let mut performances = results
+		.iter()
+		.filter_map(|result| {
+			Some((
+				result,
+				result.champion.as_deref()?,
+				result.kills_deaths_assists?,
+			))
+		})
+		.collect::<Vec<_>>();
+	performances.sort_by(|(.., a), (.., b)| kda(*a).total_cmp(&kda(*b)));
+	let performance = |(result, champion, (kills, deaths, assists)): &(&GameResult, &str, _)| {
+		format!(
+			"{champion} {kills}/{deaths}/{assists} {} ({})",
+			if result.result > 0 { "✅" } else { "❌" },
+			result.game_id
+		)
+	};
+	if let (Some(worst), Some(best)) = (performances.first(), performances.last()) {
+		lines.push(format!("Best: {}", performance(best)));
+		lines.push(format!("Worst: {}", performance(worst)));
+	}
+
+	lines.join("\n")
+}
+
+pub fn generate_tft(name: &str, period: &str, results: &[GameResult]) -> String {
+	if results.is_empty() {
+		return generate_empty(name, "TFT", period);
+	}
+
+	let placements = results.iter().map(|result| result.result).sum::<i32>();
+	let tops = results.iter().filter(|result| result.result <= 4).count();
+	let mut lines = vec![
+		format!("📊 <b>{}</b> · TFT · {period}", super::escape(name)),
+		format!(
+			"Games: {} · Average placement: {:.1} · Top 4: {:.1}%",
+			results.len(),
+			f64::from(placements) / results.len() as f64,
+			percentage(tops, results.len())
+		),
+	];
+	lines.extend(generate_league_trend(results));
+
+	let best = results.iter().min_by_key(|result| result.result).unwrap();
+	let worst = results.iter().max_by_key(|result| result.result).unwrap();
+	lines.push(format!("Best: #{} ({})", best.result, best.game_id));
+	lines.push(format!("Worst: #{} ({})", worst.result, worst.game_id));
+
+	lines.join("\n")
+}
+
+fn generate_empty(name: &str, product: &str, period: &str) -> String {
+	format!(
+		"📊 <b>{}</b> · {product} · {period}\nNo games",
+		super::escape(name)
+	)
+}
+
+// First and last league of the queue most recently played ranked
+fn generate_league_trend(results: &[GameResult]) -> Option<String> {
+	let leagues = super::ranked_results(results);
+	let ((_, first), (_, last)) = (*leagues.first()?, *leagues.last()?);
+	let total = |(tier, rank, points)| super::total_league_points(tier, rank, points);
+	Some(format!(
+		"LP: {} → {} ({:+})",
+		league_string(first),
+		league_string(last),
+		total(last) - total(first)
+	))
+}
+
+fn league_string((tier, rank, points): (Tier, Rank, i32)) -> String {
+	if tier.is_apex() {
+		format!("{tier} {points} LP")
+	} else {
+		format!("{tier} {rank} {points} LP")
+	}
+}
+
+fn percentage(part: usize, total: usize) -> f64 {
+	100.0 * part as f64 / total.max(1) as f64
+}
@@ -1,45 +1,52 @@
 use super::{
+	achievement,
+	placeholders::{self, Kind},
 	riot_api::{
 		tft::{Game, League, Participant, Player},
 		Platform, Queue, Tier,
 	},
-	riot_config::tft::{Message, MessageTemplate},
+	riot_config::{
+		tft::{Message, MessageTemplate, StreakTemplate},
+		Locale,
+	},
 };
 
 pub fn generate_messages(
 	game: &Game,
 	platform: Platform,
-	players_participants_leagues: &[(Player, Participant, Option<League>)],
+	players_participants_leagues_streaks: &[(Player, Participant, Option<League>, i32)],
 	message: &Message,
+	locale: &Locale,
 ) -> Vec<String> {
 	let is_double = game.info.queue_id
 		== i32::from(Queue::CONVERGENCE_RANKED_TEAMFIGHT_TACTICS_DOUBLE_UP_WORKSHOP_.0);
 
-	(1..=(if is_double { 4 } else { 8 }))
-		.into_iter()
+	let messages = (1..=(if is_double { 4 } else { 8 }))
 		.filter_map(|result| {
-			let players_participants_leagues = players_participants_leagues
+			let players_participants_leagues_streaks = players_participants_leagues_streaks
 				.iter()
-				.filter(|(_, participant, _)| if is_double { (participant.placement + 1) / 2} else {participant.placement} == result)
+				.filter(|(_, participant, ..)| if is_double { (participant.placement + 1) / 2} else {participant.placement} == result)
 				.collect::<Vec<_>>();
-			match players_participants_leagues.len() {
+			match players_participants_leagues_streaks.len() {
 				0 => None,
 				1 => Some(generate_message_single(
 					game,
 					platform,
 					result,
-					players_participants_leagues.first().unwrap(),
+					players_participants_leagues_streaks.first().unwrap(),
 					&message.single,
-					if is_double { 2 } else { 4 }
+					if is_double { 2 } else { 4 },
+					locale
 				)),
 				2 => Some(generate_message_duo(
 					game,
 					platform,
 					result,
-					players_participants_leagues.first().unwrap(),
-					players_participants_leagues.last().unwrap(),
+					players_participants_leagues_streaks.first().unwrap(),
+					players_participants_leagues_streaks.last().unwrap(),
 					&message.duo,
-					if is_double { 2 } else { 4 }
+					if is_double { 2 } else { 4 },
+					locale
 				)),
 				n => {
 					tracing::warn!(
@@ -49,117 +56,334 @@ pub fn generate_messages(
 					None
 				}
 			}
-		}).collect()
+		});
+	messages
+		.chain(generate_streak_messages(
+			game,
+			platform,
+			players_participants_leagues_streaks,
+			&message.streak,
+			locale,
+		))
+		.collect()
+}
+
+// Every template rendered with example values
+pub fn generate_samples(message: &Message) -> Vec<(String, String)> {
+	let sample = |template: &str| placeholders::sample(template, "TFT");
+	let Message {
+		single,
+		duo,
+		streak,
+		achievement,
+	} = message;
+	let samples = vec![
+		("single.top-single", sample(&single.top_single)),
+		(
+			"single.top-single-ranked",
+			sample(&single.top_single_ranked),
+		),
+		("single.bottom-single", sample(&single.bottom_single)),
+		(
+			"single.bottom-single-ranked",
+			sample(&single.bottom_single_ranked),
+		),
+		(
+			"duo.top-duo",
+			sample(&placeholders::replace(
+				&duo.top_duo,
+				&[("singles", duo.top_single_ranked.clone() + &duo.top_single)],
+			)),
+		),
+		(
+			"duo.bottom-duo",
+			sample(&placeholders::replace(
+				&duo.bottom_duo,
+				&[(
+					"singles",
+					duo.bottom_single_ranked.clone() + &duo.bottom_single,
+				)],
+			)),
+		),
+	];
+	let streaks = [("top", &streak.top), ("bottom", &streak.bottom)]
+		.into_iter()
+		.flat_map(|(result, templates)| {
+			templates.iter().map(move |(games, template)| {
+				(format!("streak.{result}.{games}"), sample(template))
+			})
+		});
+	samples
+		.into_iter()
+		.map(|(template, sample)| (String::from(template), sample))
+		.chain(streaks)
+		.chain(
+			(!achievement.is_empty()).then(|| (String::from("achievement"), sample(achievement))),
+		)
+		.collect()
+}
+
+// Problems of every template by path, see `placeholders::validate`
+pub fn validate(message: &Message) -> Vec<(String, String)> {
+	let Message {
+		single,
+		duo,
+		streak,
+		achievement,
+	} = message;
+	let mut paths_templates = vec![
+		("single.top-single", &single.top_single, true, Kind::Single),
+		(
+			"single.top-single-ranked",
+			&single.top_single_ranked,
+			true,
+			Kind::Single,
+		),
+		(
+			"single.bottom-single",
+			&single.bottom_single,
+			true,
+			Kind::Single,
+		),
+		(
+			"single.bottom-single-ranked",
+			&single.bottom_single_ranked,
+			true,
+			Kind::Single,
+		),
+		("duo.top-duo", &duo.top_duo, true, Kind::Multiple),
+		("duo.top-single", &duo.top_single, false, Kind::Single),
+		(
+			"duo.top-single-ranked",
+			&duo.top_single_ranked,
+			false,
+			Kind::Single,
+		),
+		("duo.bottom-duo", &duo.bottom_duo, true, Kind::Multiple),
+		("duo.bottom-single", &duo.bottom_single, false, Kind::Single),
+		(
+			"duo.bottom-single-ranked",
+			&duo.bottom_single_ranked,
+			false,
+			Kind::Single,
+		),
+		("achievement", achievement, false, Kind::Achievement),
+	]
+	.into_iter()
+	.map(|(path, template, required, kind)| (String::from(path), template.as_str(), required, kind))
+	.collect::<Vec<_>>();
+	for (result, templates) in [("top", &streak.top), ("bottom", &streak.bottom)] {
+		for (games, template) in templates {
+			paths_templates.push((
+				format!("streak.{result}.{games}"),
+				template,
+				true,
+				Kind::Streak,
+			));
+		}
+	}
+	placeholders::validate("TFT", paths_templates)
 }
 
 fn generate_message_single(
 	game: &Game,
 	platform: Platform,
 	result: i32,
-	(player, .., league): &(Player, Participant, Option<League>),
+	(player, _, league, streak): &(Player, Participant, Option<League>, i32),
 	message_template: &MessageTemplate,
 	threshold: i32,
+	locale: &Locale,
 ) -> String {
-	substitute_common(
-		game,
-		platform,
-		result,
-		if result <= threshold {
-			match league {
-				None => &message_template.top_single,
-				Some(_) => &message_template.top_single_ranked,
-			}
-		} else {
-			match league {
-				None => &message_template.bottom_single,
-				Some(_) => &message_template.bottom_single_ranked,
-			}
-		},
-	)
-	.replace("{sumoner_name}", &player.name)
-	.replace(
-		"{tier}",
-		league
-			.as_ref()
-			.and_then(|league| league.tier)
-			.unwrap_or(Tier::UNRANKED)
-			.as_ref(),
-	)
-	.replace(
-		"{rank}",
-		&league
-			.as_ref()
-			.and_then(|league| league.rank)
-			.map_or_else(String::default, |rank| rank.to_string()),
-	)
-	.replace(
-		"{lp}",
-		&format!(
-			"{}",
-			league
-				.as_ref()
-				.and_then(|league| league.league_points)
-				.unwrap_or(-1)
+	placeholders::replace(
+		&substitute_common(
+			game,
+			platform,
+			result,
+			if result <= threshold {
+				match league {
+					None => &message_template.top_single,
+					Some(_) => &message_template.top_single_ranked,
+				}
+			} else {
+				match league {
+					None => &message_template.bottom_single,
+					Some(_) => &message_template.bottom_single_ranked,
+				}
+			},
+			locale,
 		),
+		&[
+			("summoner_name", player.name.clone()),
+			(
+				"tier",
+				super::locale::tier(
+					locale,
+					league
+						.as_ref()
+						.and_then(|league| league.tier)
+						.unwrap_or(Tier::UNRANKED),
+				),
+			),
+			(
+				"rank",
+				league
+					.as_ref()
+					.and_then(|league| league.rank)
+					.map_or_else(String::default, |rank| {
+						super::locale::division(locale, rank)
+					}),
+			),
+			(
+				"lp",
+				format!(
+					"{}",
+					league
+						.as_ref()
+						.and_then(|league| league.league_points)
+						.unwrap_or(-1)
+				),
+			),
+			("streak", format!("{}", streak.abs())),
+		],
 	)
 }
 
+#[allow(clippy::too_many_arguments)]
 fn generate_message_duo(
 	game: &Game,
 	platform: Platform,
 	result: i32,
-	player_participant_league_a: &(Player, Participant, Option<League>),
-	player_participant_league_b: &(Player, Participant, Option<League>),
+	player_participant_league_streak_a: &(Player, Participant, Option<League>, i32),
+	player_participant_league_streak_b: &(Player, Participant, Option<League>, i32),
 	message_template: &MessageTemplate,
 	threshold: i32,
+	locale: &Locale,
 ) -> String {
-	let (player_a, ..) = player_participant_league_a;
-	let (player_b, ..) = player_participant_league_b;
-	substitute_common(
-		game,
-		platform,
-		result,
-		if result <= threshold {
-			&message_template.top_duo
-		} else {
-			&message_template.bottom_duo
-		},
-	)
-	.replace(
-		"{sumoner_names}",
-		&format!("{} &amp; {}", player_a.name, player_b.name),
-	)
-	.replace("{singles}", &{
-		generate_message_single(
+	let (player_a, ..) = player_participant_league_streak_a;
+	let (player_b, ..) = player_participant_league_streak_b;
+	placeholders::replace(
+		&substitute_common(
 			game,
 			platform,
 			result,
-			player_participant_league_a,
-			message_template,
-			threshold,
-		) + &generate_message_single(
-			game,
-			platform,
-			result,
-			player_participant_league_b,
-			message_template,
-			threshold,
-		)
-	})
+			if result <= threshold {
+				&message_template.top_duo
+			} else {
+				&message_template.bottom_duo
+			},
+			locale,
+		),
+		&[
+			(
+				"summoner_names",
+				format!("{} &amp; {}", player_a.name, player_b.name),
+			),
+			(
+				"singles",
+				generate_message_single(
+					game,
+					platform,
+					result,
+					player_participant_league_streak_a,
+					message_template,
+					threshold,
+					locale,
+				) + &generate_message_single(
+					game,
+					platform,
+					result,
+					player_participant_league_streak_b,
+					message_template,
+					threshold,
+					locale,
+				),
+			),
+		],
+	)
 }
 
-fn substitute_common(game: &Game, platform: Platform, result: i32, message: &str) -> String {
-	message
-		.replace("{mode}", &get_queue_or_mode_string(game))
-		.replace("{top}", &format!("{result}"))
-		.replace(
-			"{game_duration_min}",
-			&format!("{:.0}", game.info.game_length / 60.0),
-		)
-		.replace("{region}", platform.as_region_str())
+// Announcements of the achievements unlocked by a player in a game
+pub fn generate_achievement_messages(
+	game: &Game,
+	platform: Platform,
+	(player, participant): (&Player, &Participant),
+	achievements: &[String],
+	message: &Message,
+	locale: &Locale,
+) -> Vec<String> {
+	if message.achievement.is_empty() {
+		return Vec::default();
+	}
+	achievements
+		.iter()
+		.map(|achievement| {
+			placeholders::replace(
+				&substitute_common(
+					game,
+					platform,
+					participant.placement,
+					&message.achievement,
+					locale,
+				),
+				&[
+					("summoner_name", player.name.clone()),
+					("achievement", achievement::describe(achievement, locale)),
+				],
+			)
+		})
+		.collect()
 }
 
-fn get_queue_or_mode_string(game: &Game) -> String {
+// Messages of the players whose streak has just reached a configured length
+fn generate_streak_messages(
+	game: &Game,
+	platform: Platform,
+	players_participants_leagues_streaks: &[(Player, Participant, Option<League>, i32)],
+	streak_template: &StreakTemplate,
+	locale: &Locale,
+) -> Vec<String> {
+	players_participants_leagues_streaks
+		.iter()
+		.filter_map(|(player, participant, _, streak)| {
+			let templates = match *streak > 0 {
+				true => &streak_template.top,
+				false => &streak_template.bottom,
+			};
+			let template = templates.get(&streak.unsigned_abs())?;
+			Some(placeholders::replace(
+				&substitute_common(game, platform, participant.placement, template, locale),
+				&[
+					("summoner_name", player.name.clone()),
+					("streak", format!("{}", streak.abs())),
+				],
+			))
+		})
+		.collect()
+}
+
+fn substitute_common(
+	game: &Game,
+	platform: Platform,
+	result: i32,
+	message: &str,
+	locale: &Locale,
+) -> String {
+	placeholders::replace(
+		message,
+		&[
+			("mode", get_queue_or_mode_string(game, locale)),
+			("top", format!("{result}")),
+			(
+				"game_duration_min",
+				format!("{:.0}", game.info.game_length / 60.0),
+			),
+			("region", super::locale::region(locale, platform)),
+		],
+	)
+}
+
+// Unknown queues are named as normal games
+pub(super) fn get_queue_or_mode_string(game: &Game, locale: &Locale) -> String {
 	let queue_id = u16::try_from(game.info.queue_id).unwrap_or_else(|err| {
 		tracing::error!(
 			error = err.to_string(),
@@ -168,12 +392,7 @@ fn get_queue_or_mode_string(game: &Game) -> String {
 		0
 	});
 
-	match Queue(queue_id) {
-		Queue::CONVERGENCE_RANKED_TEAMFIGHT_TACTICS => String::from("RANKED"),
-		Queue::CONVERGENCE_RANKED_TEAMFIGHT_TACTICS_HYPER_ROLL_ => String::from("HYPER ROLL"),
-		Queue::CONVERGENCE_RANKED_TEAMFIGHT_TACTICS_DOUBLE_UP_WORKSHOP_ => {
-			String::from("DOUBLE UP")
-		}
-		_ => String::from("NORMAL"),
-	}
+	super::locale::queue(locale, Queue(queue_id))
+		.or_else(|| super::locale::queue(locale, Queue::CONVERGENCE_TEAMFIGHT_TACTICS))
+		.unwrap_or_default()
 }
@@ -0,0 +1,254 @@
+use std::sync::LazyLock;
+
+use hyper::{Body, Request, Response, StatusCode};
+use prometheus::{
+	HistogramOpts, HistogramVec, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+
+use super::rest;
+
+struct Metrics {
+	registry: Registry,
+	riot_requests: IntCounterVec,
+	riot_rate_limited: IntCounterVec,
+	polling_cycle: HistogramVec,
+	games_stored: IntCounterVec,
+	queue_depth: IntGaugeVec,
+	messages_sent: IntCounterVec,
+	messages_failed: IntCounterVec,
+	notification_delay: HistogramVec,
+	tasks_up: IntGaugeVec,
+	task_restarts: IntCounterVec,
+}
+
+impl Metrics {
+	fn new() -> Self {
+		let registry = Registry::new_custom(Some(String::from("telegram_riot")), None).unwrap();
+		let counter = |name: &str, help: &str, labels: &[&str]| {
+			let counter = IntCounterVec::new(Opts::new(name, help), labels).unwrap();
+			registry.register(Box::new(counter.clone())).unwrap();
+			counter
+		};
+		let gauge = |name: &str, help: &str, labels: &[&str]| {
+			let gauge = IntGaugeVec::new(Opts::new(name, help), labels).unwrap();
+			registry.register(Box::new(gauge.clone())).unwrap();
+			gauge
+		};
+		let histogram = |name: &str, help: &str, labels: &[&str], buckets: &[f64]| {
+			let histogram = HistogramVec::new(
+				HistogramOpts::new(name, help).buckets(buckets.to_vec()),
+				labels,
+			)
+			.unwrap();
+			registry.register(Box::new(histogram.clone())).unwrap();
+			histogram
+		};
+
+		Self {
+			riot_requests: counter(
+				"riot_requests_total",
+				"Riot API responses by endpoint and status",
+				&["product", "endpoint", "status"],
+			),
+			riot_rate_limited: counter(
+				"riot_rate_limited_total",
+				"Riot API requests rejected with 429 Too Many Requests",
+				&["product", "endpoint"],
+			),
+			polling_cycle: histogram(
+				"polling_cycle_seconds",
+				"Duration of polling a player for new games",
+				&["product"],
+				&[0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0],
+			),
+			games_stored: counter(
+				"games_stored_total",
+				"Games stored while running",
+				&["product"],
+			),
+			queue_depth: gauge(
+				"queue_depth",
+				"Items waiting in an internal queue",
+				&["queue"],
+			),
+			messages_sent: counter("messages_sent_total", "Messages sent by sink", &["sink"]),
+			messages_failed: counter(
+				"messages_failed_total",
+				"Messages failed by sink, before being sent again",
+				&["sink"],
+			),
+			notification_delay: histogram(
+				"notification_delay_seconds",
+				"Time from the end of a game to each of its messages being sent",
+				&["product"],
+				&[
+					30.0, 60.0, 120.0, 300.0, 600.0, 1200.0, 1800.0, 3600.0, 7200.0,
+				],
+			),
+			tasks_up: gauge("task_up", "Whether a task is running", &["task"]),
+			task_restarts: counter(
+				"task_restarts_total",
+				"Tasks restarted after ending or panicking",
+				&["task"],
+			),
+			registry,
+		}
+	}
+}
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+pub fn riot_request(product: &str, endpoint: &str, status: &str) {
+	METRICS
+		.riot_requests
+		.with_label_values(&[product, endpoint, status])
+		.inc();
+	if status == StatusCode::TOO_MANY_REQUESTS.as_str() {
+		METRICS
+			.riot_rate_limited
+			.with_label_values(&[product, endpoint])
+			.inc();
+	}
+}
+
+// Times a player poll until dropped
+pub struct PollingCycle(&'static str, tokio::time::Instant);
+
+impl PollingCycle {
+	pub fn start(product: &'static str) -> Self {
+		PollingCycle(product, tokio::time::Instant::now())
+	}
+}
+
+impl Drop for PollingCycle {
+	fn drop(&mut self) {
+		METRICS
+			.polling_cycle
+			.with_label_values(&[self.0])
+			.observe(self.1.elapsed().as_secs_f64());
+	}
+}
+
+pub fn game_stored(product: &str) {
+	METRICS.games_stored.with_label_values(&[product]).inc();
+}
+
+// Sent items are counted up and received ones down
+pub fn queued(queue: &str, delta: i64) {
+	METRICS.queue_depth.with_label_values(&[queue]).add(delta);
+}
+
+pub fn message_sent(sink: &str, sent: bool) {
+	match sent {
+		true => METRICS.messages_sent.with_label_values(&[sink]).inc(),
+		false => METRICS.messages_failed.with_label_values(&[sink]).inc(),
+	}
+}
+
+// From the end of a game (milliseconds)
+pub fn notified(product: &str, game_end: i64) {
+	let delay = chrono::Utc::now().timestamp_millis() - game_end;
+	METRICS
+		.notification_delay
+		.with_label_values(&[product])
+		.observe(delay.max(0) as f64 / 1000.0);
+}
+
+// Marks a task as running until dropped, `/healthz` fails while any is not
+pub struct Task(&'static str);
+
+impl Task {
+	pub fn up(task: &'static str) -> Self {
+		METRICS.tasks_up.with_label_values(&[task]).set(1);
+		Task(task)
+	}
+}
+
+impl Drop for Task {
+	fn drop(&mut self) {
+		METRICS.tasks_up.with_label_values(&[self.0]).set(0);
+	}
+}
+
+pub fn task_restarted(task: &str) {
+	METRICS.task_restarts.with_label_values(&[task]).inc();
+}
+
+fn healthy() -> bool {
+	prometheus::core::Collector::collect(&METRICS.tasks_up)
+		.iter()
+		.flat_map(|family| family.get_metric())
+		.all(|metric| metric.get_gauge().get_value() > 0.0)
+}
+
+// Prometheus `/metrics` and `/healthz`, without authentication
+pub async fn serve(builder: rest::Builder) -> Result<(), String> {
+	builder
+		.serve(hyper::service::make_service_fn(|_| async {
+			Ok::<_, std::convert::Infallible>(hyper::service::service_fn(|request| async move {
+				Ok::<_, std::convert::Infallible>(handle(request))
+			}))
+		}))
+		.await
+		.map_err(|err| format!("Error serving metrics: {err}"))
+}
+
+fn handle(request: Request<Body>) -> Response<Body> {
+	let (status, body) = match request.uri().path() {
+		"/metrics" => match TextEncoder::new().encode_to_string(&METRICS.registry.gather()) {
+			Ok(metrics) => (StatusCode::OK, metrics),
+			Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+		},
+		"/healthz" if healthy() => (StatusCode::OK, String::from("ok")),
+		"/healthz" => (
+			StatusCode::SERVICE_UNAVAILABLE,
+			String::from("A task has stopped"),
+		),
+		_ => (StatusCode::NOT_FOUND, String::from("Not found")),
+	};
+	let mut response = Response::new(Body::from(body));
+	*response.status_mut() = status;
+	response
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use super::*;
+
+	// Held by the tests running tasks, as `/healthz` depends on every task
+	static TASKS: LazyLock<tokio::sync::Mutex<()>> = LazyLock::new(Default::default);
+
+	pub(crate) async fn lock_tasks() -> tokio::sync::MutexGuard<'static, ()> {
+		TASKS.lock().await
+	}
+
+	// Stop reporting a task that a test has run
+	pub(crate) fn forget_task(task: &str) {
+		let _ = METRICS.tasks_up.remove_label_values(&[task]);
+	}
+
+	async fn get(path: &str) -> (StatusCode, String) {
+		let response = handle(Request::get(path).body(Body::empty()).unwrap());
+		let status = response.status();
+		let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+		(status, String::from_utf8(body.to_vec()).unwrap())
+	}
+
+	#[tokio::test]
+	async fn endpoints() {
+		let _tasks = lock_tasks().await;
+		riot_request("lol", "game", "429");
+		let task = Task::up("metrics_endpoints");
+		assert_eq!(get("/healthz").await, (StatusCode::OK, String::from("ok")));
+		let (status, metrics) = get("/metrics").await;
+		assert_eq!(status, StatusCode::OK);
+		assert!(metrics.contains(
+			r#"telegram_riot_riot_requests_total{endpoint="game",product="lol",status="429"} 1"#
+		));
+		assert!(metrics
+			.contains(r#"telegram_riot_riot_rate_limited_total{endpoint="game",product="lol"} 1"#));
+		drop(task);
+		assert_eq!(get("/healthz").await.0, StatusCode::SERVICE_UNAVAILABLE);
+		forget_task("metrics_endpoints");
+	}
+}
@@ -0,0 +1,384 @@
+use std::{
+	collections::BTreeMap,
+	sync::atomic::{AtomicU64, Ordering},
+};
+
+use super::{
+	api, config,
+	message::{Message, RichText},
+};
+
+pub mod discord;
+pub mod matrix;
+pub mod slack;
+pub mod telegram;
+
+// Destination of the messages of a tracker
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Chat {
+	Telegram(api::telegram::ChatId),
+	// Named in `sinks` of the configuration
+	Sink(String),
+}
+
+pub trait Notifier {
+	// Failures are logged by the notifier. `id` is the same for every attempt at
+	// a message, for services that deduplicate retries.
+	async fn send(&self, message: &Message, id: u64) -> Result<(), Error>;
+}
+
+#[derive(Debug)]
+pub enum Error {
+	// Refused by the service (e.g. a deleted webhook), so sending the message
+	// again would fail the same way
+	Rejected(String),
+	// Network errors, server errors and rate limits
+	Failed(String),
+}
+
+impl std::fmt::Display for Error {
+	fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let (Error::Rejected(err) | Error::Failed(err)) = self;
+		formatter.write_str(err)
+	}
+}
+
+// Message waiting to be sent, with the failed attempts at it so far
+pub struct Queued {
+	pub chat: Chat,
+	pub message: Message,
+	// Product and end (milliseconds) of the game it announces
+	pub game_end: Option<(&'static str, i64)>,
+	pub id: u64,
+	pub attempts: u32,
+}
+
+impl Queued {
+	pub fn new(chat: Chat, message: Message, game_end: Option<(&'static str, i64)>) -> Self {
+		static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+		Queued {
+			chat,
+			message,
+			game_end,
+			id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+			attempts: 0,
+		}
+	}
+}
+
+pub enum Sink {
+	Telegram(telegram::Telegram),
+	Discord(discord::Discord),
+	Slack(slack::Slack),
+	Matrix(matrix::Matrix),
+}
+
+impl Sink {
+	pub fn new(sink: &config::Sink, client: reqwest::Client) -> Result<Self, String> {
+		Ok(match sink {
+			config::Sink::Discord { webhook_url } => Sink::Discord(discord::Discord::new(
+				client,
+				String::from(webhook_url.expose()),
+			)),
+			config::Sink::Slack { webhook_url } => Sink::Slack(slack::Slack::new(
+				client,
+				String::from(webhook_url.expose()),
+			)),
+			config::Sink::Matrix {
+				homeserver,
+				access_token,
+				room_id,
+			} => Sink::Matrix(matrix::Matrix::new(
+				client,
+				homeserver,
+				String::from(access_token.expose()),
+				room_id.clone(),
+			)?),
+		})
+	}
+
+	pub fn kind(&self) -> &'static str {
+		match self {
+			Sink::Telegram(_) => "telegram",
+			Sink::Discord(_) => "discord",
+			Sink::Slack(_) => "slack",
+			Sink::Matrix(_) => "matrix",
+		}
+	}
+}
+
+impl Notifier for Sink {
+	async fn send(&self, message: &Message, id: u64) -> Result<(), Error> {
+		match self {
+			Sink::Telegram(telegram) => telegram.send(message, id).await,
+			Sink::Discord(discord) => discord.send(message, id).await,
+			Sink::Slack(slack) => slack.send(message, id).await,
+			Sink::Matrix(matrix) => matrix.send(message, id).await,
+		}
+	}
+}
+
+// Sink of every chat, Telegram chats share a throttled API instance, only
+// created with Telegram chats
+pub fn sinks(config: &config::Config, chats: &[Chat]) -> Result<BTreeMap<Chat, Sink>, String> {
+	let mut telegram_api = None;
+	let client = reqwest::Client::new();
+	let mut sinks = BTreeMap::default();
+	for chat in chats {
+		let sink = match chat {
+			Chat::Telegram(telegram_chat) => {
+				if config.telegram_api_key.expose().is_empty() {
+					return Err(String::from(
+						"Missing secret telegram-api-key: set it in the configuration or in TELEGRAM_API_KEY",
+					));
+				}
+				let telegram_api = telegram_api.get_or_insert_with(|| {
+					api::telegram::Throttle::new_spawn(
+						api::telegram::Api::new(config.telegram_api_key.expose()),
+						api::telegram::Limits::default(),
+					)
+				});
+				Sink::Telegram(telegram::Telegram::new(
+					telegram_api.clone(),
+					*telegram_chat,
+				))
+			}
+			Chat::Sink(name) => Sink::new(
+				config
+					.sinks
+					.get(name)
+					.ok_or_else(|| format!("Unknown sink \"{name}\""))?,
+				client.clone(),
+			)?,
+		};
+		sinks.insert(chat.clone(), sink);
+	}
+	Ok(sinks)
+}
+
+// Webhook and homeserver requests, failed or rejected ones are logged without
+// their URL, which may hold a token. Client errors other than timeouts and rate
+// limits are rejections.
+async fn request(request: reqwest::RequestBuilder, sink: &str) -> Result<reqwest::Response, Error> {
+	request
+		.send()
+		.await
+		.and_then(reqwest::Response::error_for_status)
+		.map_err(|err| {
+			let rejected = err.status().is_some_and(|status| {
+				status.is_client_error()
+					&& status != reqwest::StatusCode::REQUEST_TIMEOUT
+					&& status != reqwest::StatusCode::TOO_MANY_REQUESTS
+			});
+			let err = err.without_url().to_string();
+			match rejected {
+				true => Error::Rejected(err),
+				false => Error::Failed(err),
+			}
+		})
+		.inspect_err(|err| tracing::error!(error = err.to_string(), sink, "Error sending message"))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+	use super::*;
+
+	pub(crate) type Requests = tokio::sync::mpsc::UnboundedReceiver<(String, Vec<u8>)>;
+
+	// Local HTTP server answering `response` to every request, with the head and
+	// body of the requests it receives
+	async fn stand_in(response: &'static str) -> (String, Requests) {
+		stand_in_answering(vec![(200, response)]).await
+	}
+
+	// As `stand_in`, answering with the statuses and bodies of `answers` in
+	// turn, then with the last one
+	pub(crate) async fn stand_in_answering(
+		answers: Vec<(u16, &'static str)>,
+	) -> (String, Requests) {
+		let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let url = format!("http://{}", listener.local_addr().unwrap());
+		let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+		tokio::spawn(async move {
+			let mut answers = answers
+				.iter()
+				.chain(std::iter::repeat(answers.last().unwrap()));
+			while let Ok((mut stream, _)) = listener.accept().await {
+				let (status, response) = answers.next().unwrap();
+				let mut buffer = Vec::default();
+				let mut chunk = [0; 4096];
+				let (head, length) = loop {
+					let read = stream.read(&mut chunk).await.unwrap();
+					buffer.extend_from_slice(&chunk[..read]);
+					if let Some(end) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+						let head = String::from_utf8_lossy(&buffer[..end]).to_string();
+						let length = head
+							.lines()
+							.filter_map(|line| line.split_once(':'))
+							.find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+							.map_or(0, |(_, value)| value.trim().parse().unwrap());
+						buffer.drain(..end + 4);
+						break (head, length);
+					}
+				};
+				while buffer.len() < length {
+					let read = stream.read(&mut chunk).await.unwrap();
+					buffer.extend_from_slice(&chunk[..read]);
+				}
+				stream
+					.write_all(
+						format!(
+							"HTTP/1.1 {status} Stand-in\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+							response.len()
+						)
+						.as_bytes(),
+					)
+					.await
+					.unwrap();
+				sender.send((head, buffer)).unwrap();
+			}
+		});
+		(url, receiver)
+	}
+
+	fn message() -> Message {
+		Message::html(
+			"<b>Win</b> &amp; <i>co</i>\n<u>KDA</u> <a href=\"https://example.org\">10/2/8</a>",
+		)
+	}
+
+	#[tokio::test]
+	async fn telegram() {
+		let (url, mut requests) = stand_in(
+			r#"{"ok":true,"result":{"message_id":1,"date":0,"chat":{"id":42,"type":"private","first_name":"A"},"text":"Win"}}"#,
+		)
+		.await;
+		let telegram = telegram::Telegram::new(
+			api::telegram::Throttle::new_spawn(
+				api::telegram::Api::new("TOKEN").set_api_url(url.parse().unwrap()),
+				api::telegram::Limits::default(),
+			),
+			api::telegram::ChatId(42),
+		);
+		telegram.send(&message(), 0).await.unwrap();
+
+		let (head, body) = requests.recv().await.unwrap();
+		assert!(head
+			.to_lowercase()
+			.starts_with("post /bottoken/sendmessage "));
+		let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+		assert_eq!(body["chat_id"], 42);
+		assert_eq!(body["parse_mode"], "HTML");
+		assert_eq!(
+			body["text"],
+			"<b>Win</b> &amp; <i>co</i>\n<u>KDA</u> <a href=\"https://example.org\">10/2/8</a>"
+		);
+
+		// Sent as written, even what other sinks cannot render
+		let html =
+			"<blockquote>Win</blockquote> <tg-spoiler>1st</tg-spoiler>\n<pre>10/2/8\n25000</pre>";
+		telegram.send(&Message::html(html), 1).await.unwrap();
+		let (_, body) = requests.recv().await.unwrap();
+		let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+		assert_eq!(body["text"], html);
+	}
+
+	#[tokio::test]
+	async fn discord() {
+		let (url, mut requests) = stand_in("").await;
+		let discord = discord::Discord::new(reqwest::Client::new(), format!("{url}/webhook"));
+		discord.send(&message(), 0).await.unwrap();
+
+		let (head, body) = requests.recv().await.unwrap();
+		assert!(head.starts_with("POST /webhook "));
+		let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+		assert_eq!(
+			body["content"],
+			"**Win** & *co*\n__KDA__ [10/2/8](https://example.org)"
+		);
+
+		discord
+			.send(&Message::photo(vec![1, 2, 3], "<b>Card</b>"), 1)
+			.await
+			.unwrap();
+		let (head, body) = requests.recv().await.unwrap();
+		assert!(head.contains("multipart/form-data"));
+		let body = String::from_utf8_lossy(&body);
+		assert!(body.contains(r#"{"content":"**Card**"}"#));
+		assert!(body.contains("filename=\"card.png\""));
+
+		// Deleted webhooks are rejections, rate limits are not
+		let (url, _requests) = stand_in_answering(vec![(404, ""), (429, "")]).await;
+		let discord = discord::Discord::new(reqwest::Client::new(), format!("{url}/webhook"));
+		assert!(matches!(
+			discord.send(&message(), 0).await,
+			Err(Error::Rejected(_))
+		));
+		assert!(matches!(
+			discord.send(&message(), 0).await,
+			Err(Error::Failed(_))
+		));
+	}
+
+	#[tokio::test]
+	async fn slack() {
+		let (url, mut requests) = stand_in("ok").await;
+		let slack = slack::Slack::new(reqwest::Client::new(), format!("{url}/webhook"));
+		slack.send(&message(), 0).await.unwrap();
+
+		let (head, body) = requests.recv().await.unwrap();
+		assert!(head.starts_with("POST /webhook "));
+		let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+		assert_eq!(
+			body["text"],
+			"*Win* &amp; _co_\nKDA <https://example.org|10/2/8>"
+		);
+	}
+
+	#[tokio::test]
+	async fn matrix() {
+		let (url, mut requests) = stand_in(r#"{"content_uri":"mxc://example.org/card"}"#).await;
+		let matrix = matrix::Matrix::new(
+			reqwest::Client::new(),
+			&url,
+			String::from("TOKEN"),
+			String::from("!room:example.org"),
+		)
+		.unwrap();
+		matrix.send(&message(), 0).await.unwrap();
+
+		let (head, body) = requests.recv().await.unwrap();
+		assert!(
+			head.starts_with("PUT /_matrix/client/v3/rooms/!room:example.org/send/m.room.message/")
+		);
+		// Attempts at the same message reuse its transaction
+		matrix.send(&message(), 0).await.unwrap();
+		let (again, _) = requests.recv().await.unwrap();
+		assert_eq!(again.lines().next(), head.lines().next());
+		assert!(head.to_lowercase().contains("authorization: bearer token"));
+		let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+		assert_eq!(body["msgtype"], "m.text");
+		assert_eq!(body["body"], "Win & co\nKDA 10/2/8");
+		assert_eq!(
+			body["formatted_body"],
+			"<b>Win</b> &amp; <i>co</i>\n<u>KDA</u> <a href=\"https://example.org\">10/2/8</a>"
+		);
+
+		matrix
+			.send(&Message::photo(vec![1, 2, 3], "<b>Card</b>"), 1)
+			.await
+			.unwrap();
+		let (head, body) = requests.recv().await.unwrap();
+		assert!(head.starts_with("POST /_matrix/media/v3/upload?filename=card.png "));
+		assert_eq!(body, [1, 2, 3]);
+		let (_, body) = requests.recv().await.unwrap();
+		let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+		assert_eq!(body["msgtype"], "m.image");
+		assert_eq!(body["url"], "mxc://example.org/card");
+		let (_, body) = requests.recv().await.unwrap();
+		let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+		assert_eq!(body["formatted_body"], "<b>Card</b>");
+	}
+}
@@ -0,0 +1,74 @@
+use super::{Error, Message, Notifier, RichText};
+
+pub struct Discord {
+	client: reqwest::Client,
+	webhook_url: String,
+}
+
+impl Discord {
+	pub fn new(client: reqwest::Client, webhook_url: String) -> Self {
+		Discord {
+			client,
+			webhook_url,
+		}
+	}
+}
+
+impl Notifier for Discord {
+	async fn send(&self, message: &Message, _id: u64) -> Result<(), Error> {
+		let request = match message {
+			Message::Text(text) => self
+				.client
+				.post(&self.webhook_url)
+				.json(&serde_json::json!({ "content": render(&RichText::from_html(text)) })),
+			Message::Photo(photo, caption) => {
+				let file = reqwest::multipart::Part::bytes(photo.clone())
+					.file_name("card.png")
+					.mime_str("image/png")
+					.map_err(|err| Error::Failed(err.to_string()))?;
+				self.client.post(&self.webhook_url).multipart(
+					reqwest::multipart::Form::new()
+						.text(
+							"payload_json",
+							serde_json::json!({ "content": render(&RichText::from_html(caption)) })
+								.to_string(),
+						)
+						.part("files[0]", file),
+				)
+			}
+		};
+		super::request(request, "Discord").await.map(|_| ())
+	}
+}
+
+// Discord Markdown
+fn render(text: &RichText) -> String {
+	text.render(|text, style| {
+		let mut markdown = if style.code {
+			format!("`{}`", text.replace('`', "'"))
+		} else {
+			let mut escaped = String::default();
+			for character in text.chars() {
+				if "\\*_~`|".contains(character) {
+					escaped.push('\\');
+				}
+				escaped.push(character);
+			}
+			escaped
+		};
+		for (enabled, marker) in [
+			(style.strikethrough, "~~"),
+			(style.underline, "__"),
+			(style.italic, "*"),
+			(style.bold, "**"),
+		] {
+			if enabled {
+				markdown = format!("{marker}{markdown}{marker}");
+			}
+		}
+		match &style.link {
+			Some(link) => format!("[{markdown}]({link})"),
+			None => markdown,
+		}
+	})
+}
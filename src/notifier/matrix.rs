@@ -0,0 +1,132 @@
+use super::{Error, Message, Notifier, RichText};
+
+pub struct Matrix {
+	client: reqwest::Client,
+	homeserver: reqwest::Url,
+	access_token: String,
+	room_id: String,
+	// Transaction identifiers must be unique across restarts
+	transaction_prefix: i64,
+}
+
+impl Matrix {
+	pub fn new(
+		client: reqwest::Client,
+		homeserver: &str,
+		access_token: String,
+		room_id: String,
+	) -> Result<Self, String> {
+		let homeserver = reqwest::Url::parse(homeserver)
+			.ok()
+			.filter(|url| !url.cannot_be_a_base())
+			.ok_or_else(|| format!("Invalid Matrix homeserver \"{homeserver}\""))?;
+		Ok(Matrix {
+			client,
+			homeserver,
+			access_token,
+			room_id,
+			transaction_prefix: chrono::Utc::now().timestamp_millis(),
+		})
+	}
+
+	fn url(&self, segments: &[&str]) -> reqwest::Url {
+		let mut url = self.homeserver.clone();
+		if let Ok(mut path) = url.path_segments_mut() {
+			path.pop_if_empty().extend(segments);
+		}
+		url
+	}
+
+	// The `event` of message `id` keeps its transaction identifier across
+	// attempts, so that the homeserver does not post it twice
+	async fn send_event(
+		&self,
+		content: serde_json::Value,
+		id: u64,
+		event: u32,
+	) -> Result<(), Error> {
+		let transaction = format!("{}-{id}-{event}", self.transaction_prefix);
+		let url = self.url(&[
+			"_matrix",
+			"client",
+			"v3",
+			"rooms",
+			&self.room_id,
+			"send",
+			"m.room.message",
+			&transaction,
+		]);
+		super::request(
+			self.client
+				.put(url)
+				.bearer_auth(&self.access_token)
+				.json(&content),
+			"Matrix",
+		)
+		.await
+		.map(|_| ())
+	}
+
+	async fn send_text(&self, text: &RichText, id: u64, event: u32) -> Result<(), Error> {
+		self.send_event(
+			serde_json::json!({
+				"msgtype": "m.text",
+				"body": text.to_plain(),
+				"format": "org.matrix.custom.html",
+				"formatted_body": text.to_html(),
+			}),
+			id,
+			event,
+		)
+		.await
+	}
+
+	// Uploaded to the media repository, then sent followed by its caption
+	async fn send_photo(&self, photo: &[u8], caption: &RichText, id: u64) -> Result<(), Error> {
+		let mut url = self.url(&["_matrix", "media", "v3", "upload"]);
+		url.query_pairs_mut().append_pair("filename", "card.png");
+		let response = super::request(
+			self.client
+				.post(url)
+				.bearer_auth(&self.access_token)
+				.header(reqwest::header::CONTENT_TYPE, "image/png")
+				.body(photo.to_vec()),
+			"Matrix",
+		)
+		.await?;
+		let content_uri = response
+			.json::<serde_json::Value>()
+			.await
+			.ok()
+			.and_then(|upload| upload["content_uri"].as_str().map(String::from))
+			.ok_or_else(|| Error::Failed(String::from("Matrix upload without content URI")))?;
+
+		self.send_event(
+			serde_json::json!({
+				"msgtype": "m.image",
+				"body": "card.png",
+				"url": content_uri,
+				"info": { "mimetype": "image/png", "size": photo.len() },
+			}),
+			id,
+			0,
+		)
+		.await?;
+		if caption.0.is_empty() {
+			return Ok(());
+		}
+		self.send_text(caption, id, 1).await
+	}
+}
+
+impl Notifier for Matrix {
+	async fn send(&self, message: &Message, id: u64) -> Result<(), Error> {
+		match message {
+			Message::Text(text) => self.send_text(&RichText::from_html(text), id, 0).await,
+			Message::Photo(photo, caption) => {
+				self.send_photo(photo, &RichText::from_html(caption), id)
+					.await
+			}
+		}
+	}
+}
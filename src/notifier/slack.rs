@@ -0,0 +1,54 @@
+use super::{Error, Message, Notifier, RichText};
+
+pub struct Slack {
+	client: reqwest::Client,
+	webhook_url: String,
+}
+
+impl Slack {
+	pub fn new(client: reqwest::Client, webhook_url: String) -> Self {
+		Slack {
+			client,
+			webhook_url,
+		}
+	}
+}
+
+impl Notifier for Slack {
+	// Incoming webhooks cannot upload files, photos are sent as their caption
+	async fn send(&self, message: &Message, _id: u64) -> Result<(), Error> {
+		let (Message::Text(text) | Message::Photo(_, text)) = message;
+		super::request(
+			self.client
+				.post(&self.webhook_url)
+				.json(&serde_json::json!({ "text": render(&RichText::from_html(text)) })),
+			"Slack",
+		)
+		.await
+		.map(|_| ())
+	}
+}
+
+// Slack mrkdwn, which has no underline
+fn render(text: &RichText) -> String {
+	text.render(|text, style| {
+		let mut mrkdwn = text
+			.replace('&', "&amp;")
+			.replace('<', "&lt;")
+			.replace('>', "&gt;");
+		for (enabled, marker) in [
+			(style.code, "`"),
+			(style.strikethrough, "~"),
+			(style.italic, "_"),
+			(style.bold, "*"),
+		] {
+			if enabled {
+				mrkdwn = format!("{marker}{mrkdwn}{marker}");
+			}
+		}
+		match &style.link {
+			Some(link) => format!("<{link}|{mrkdwn}>"),
+			None => mrkdwn,
+		}
+	})
+}
@@ -0,0 +1,32 @@
+use super::{api, Error, Message, Notifier};
+
+pub struct Telegram {
+	api: api::telegram::Throttle<api::telegram::Api>,
+	chat: api::telegram::ChatId,
+}
+
+impl Telegram {
+	pub fn new(
+		api: api::telegram::Throttle<api::telegram::Api>,
+		chat: api::telegram::ChatId,
+	) -> Self {
+		Telegram { api, chat }
+	}
+}
+
+impl Notifier for Telegram {
+	// Templates are Telegram HTML, sent as written
+	async fn send(&self, message: &Message, _id: u64) -> Result<(), Error> {
+		match message {
+			Message::Text(text) => api::telegram::send_message(&self.api, self.chat, text).await,
+			Message::Photo(photo, caption) => {
+				api::telegram::send_photo(&self.api, self.chat, photo, caption).await
+			}
+		}
+		.map_err(|err| match err {
+			api::telegram::RequestError::Api(_)
+			| api::telegram::RequestError::MigrateToChatId(_) => Error::Rejected(err.to_string()),
+			_ => Error::Failed(err.to_string()),
+		})
+	}
+}
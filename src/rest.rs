@@ -0,0 +1,476 @@
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	convert::Infallible,
+	sync::Arc,
+};
+
+use hyper::{Body, Method, Request, Response, StatusCode};
+use serde_json::json;
+
+use super::{api, db, message, notifier};
+
+type Tracked = BTreeMap<(String, api::riot::Platform), BTreeSet<notifier::Chat>>;
+pub type Builder = hyper::server::Builder<hyper::server::conn::AddrIncoming>;
+
+// Shared by every request
+struct State {
+	db_pool: db::Pool,
+	token: String,
+	lol_names_platforms_chats: Tracked,
+	tft_names_platforms_chats: Tracked,
+}
+
+// Listening before the tasks start, so a bad address is a startup error
+pub fn bind(listen: &str) -> Result<Builder, String> {
+	let address: std::net::SocketAddr = listen
+		.parse()
+		.map_err(|_| format!("Invalid listen address \"{listen}\""))?;
+	hyper::Server::try_bind(&address).map_err(|err| format!("Error listening on {address}: {err}"))
+}
+
+pub async fn serve(
+	builder: Builder,
+	token: String,
+	db_pool: db::Pool,
+	lol_names_platforms_chats: Tracked,
+	tft_names_platforms_chats: Tracked,
+) -> Result<(), String> {
+	let state = Arc::new(State {
+		db_pool,
+		token,
+		lol_names_platforms_chats,
+		tft_names_platforms_chats,
+	});
+	builder
+		.serve(hyper::service::make_service_fn(move |_| {
+			let state = state.clone();
+			async move {
+				Ok::<_, Infallible>(hyper::service::service_fn(move |request| {
+					let state = state.clone();
+					async move { Ok::<_, Infallible>(handle(&state, request).await) }
+				}))
+			}
+		}))
+		.await
+		.map_err(|err| format!("Error serving REST API: {err}"))
+}
+
+async fn handle(state: &State, request: Request<Body>) -> Response<Body> {
+	let authorized = request
+		.headers()
+		.get(hyper::header::AUTHORIZATION)
+		.and_then(|authorization| authorization.to_str().ok())
+		.and_then(|authorization| authorization.strip_prefix("Bearer "))
+		.is_some_and(|token| same_token(token, &state.token));
+	if !authorized {
+		return respond(StatusCode::UNAUTHORIZED, json!({ "error": "Unauthorized" }));
+	}
+	if request.method() != Method::GET {
+		return respond(
+			StatusCode::METHOD_NOT_ALLOWED,
+			json!({ "error": "Method not allowed" }),
+		);
+	}
+
+	let query = request
+		.uri()
+		.query()
+		.unwrap_or_default()
+		.split('&')
+		.filter_map(|pair| pair.split_once('='))
+		.collect::<BTreeMap<_, _>>();
+	let path = request
+		.uri()
+		.path()
+		.trim_matches('/')
+		.split('/')
+		.collect::<Vec<_>>();
+	let body = match path.as_slice() {
+		["players"] => players(state).await,
+		["players", puuid, "games"] => player_games(state, puuid, &query).await,
+		["games", game_id] => game(state, game_id).await,
+		["leaderboard"] => leaderboard(state, &query).await,
+		["chats", chat, "trackers"] => Ok(chat_trackers(state, chat)),
+		_ => Ok(None),
+	};
+	match body {
+		Ok(Some(body)) => respond(StatusCode::OK, body),
+		Ok(None) => respond(StatusCode::NOT_FOUND, json!({ "error": "Not found" })),
+		Err(_) => respond(
+			StatusCode::INTERNAL_SERVER_ERROR,
+			json!({ "error": "Error reading DB" }),
+		),
+	}
+}
+
+// Compared in constant time, so that response times do not leak the token
+fn same_token(token: &str, expected: &str) -> bool {
+	token.len() == expected.len()
+		&& token
+			.bytes()
+			.zip(expected.bytes())
+			.fold(0, |difference, (a, b)| difference | (a ^ b))
+			== 0
+}
+
+fn respond(status: StatusCode, body: serde_json::Value) -> Response<Body> {
+	let mut response = Response::new(Body::from(body.to_string()));
+	*response.status_mut() = status;
+	response.headers_mut().insert(
+		hyper::header::CONTENT_TYPE,
+		hyper::header::HeaderValue::from_static("application/json"),
+	);
+	response
+}
+
+fn league(league: Option<(api::riot::Tier, api::riot::Rank, i32)>) -> serde_json::Value {
+	json!(league.map(|(tier, rank, points)| {
+		json!({ "tier": tier.as_ref(), "rank": rank.to_string(), "lp": points })
+	}))
+}
+
+// Every stored player
+async fn players(state: &State) -> db::Result<Option<serde_json::Value>> {
+	let mut players = Vec::default();
+	for (product, players_) in [
+		("lol", db::riot::lol::get_players(&state.db_pool).await?),
+		("tft", db::riot::tft::get_players(&state.db_pool).await?),
+	] {
+		for (puuid, name) in players_ {
+			players.push(json!({ "product": product, "puuid": puuid, "name": name }));
+		}
+	}
+	Ok(Some(json!(players)))
+}
+
+// Results of a player since `since` (milliseconds, all by default), oldest first
+async fn player_games(
+	state: &State,
+	puuid: &str,
+	query: &BTreeMap<&str, &str>,
+) -> db::Result<Option<serde_json::Value>> {
+	let since = query
+		.get("since")
+		.and_then(|since| since.parse().ok())
+		.unwrap_or_default();
+	let mut games = Vec::default();
+	for (product, results) in [
+		(
+			"lol",
+			db::riot::lol::get_results_by_id(&state.db_pool, puuid, since).await?,
+		),
+		(
+			"tft",
+			db::riot::tft::get_results_by_id(&state.db_pool, puuid, since).await?,
+		),
+	] {
+		for result in results {
+			games.push(json!({
+				"product": product,
+				"match_id": result.game_id,
+				"queue": result.queue,
+				"timestamp": result.timestamp,
+				"result": result.result,
+				"league": league(result.league),
+				"champion": result.champion,
+				"kills_deaths_assists": result.kills_deaths_assists,
+			}));
+		}
+	}
+	Ok(Some(json!(games)))
+}
+
+// A stored game with its stored players
+async fn game(state: &State, game_id: &str) -> db::Result<Option<serde_json::Value>> {
+	let (product, summary) = match db::riot::lol::get_game_summary(&state.db_pool, game_id).await? {
+		Some(summary) => ("lol", summary),
+		None => match db::riot::tft::get_game_summary(&state.db_pool, game_id).await? {
+			Some(summary) => ("tft", summary),
+			None => return Ok(None),
+		},
+	};
+	let (platform, queue, timestamp, results) = summary;
+	Ok(Some(json!({
+		"product": product,
+		"match_id": game_id,
+		"platform": platform.to_string(),
+		"queue": queue,
+		"timestamp": timestamp,
+		"participants": results
+			.into_iter()
+			.map(|(puuid, name, result, league_)| json!({
+				"puuid": puuid,
+				"name": name,
+				"result": result,
+				"league": league(league_),
+			}))
+			.collect::<Vec<_>>(),
+	})))
+}
+
+// Latest league of every player by product and queue, highest first, optionally
+// of a `product` and a `queue`
+async fn leaderboard(
+	state: &State,
+	query: &BTreeMap<&str, &str>,
+) -> db::Result<Option<serde_json::Value>> {
+	let queue = query
+		.get("queue")
+		.and_then(|queue| queue.parse::<i32>().ok());
+	let mut entries = Vec::default();
+	for product in ["lol", "tft"] {
+		if query
+			.get("product")
+			.is_some_and(|filter| *filter != product)
+		{
+			continue;
+		}
+		let mut leagues = match product {
+			"lol" => db::riot::lol::get_leagues(&state.db_pool).await?,
+			_ => db::riot::tft::get_leagues(&state.db_pool).await?,
+		};
+		leagues.retain(|(.., league_queue, _)| queue.is_none_or(|queue| queue == *league_queue));
+		leagues.sort_by_key(|(.., queue, (tier, rank, points))| {
+			(
+				*queue,
+				std::cmp::Reverse(message::riot::total_league_points(*tier, *rank, *points)),
+			)
+		});
+		for (puuid, name, queue, league_) in leagues {
+			entries.push(json!({
+				"product": product,
+				"queue": queue,
+				"puuid": puuid,
+				"name": name,
+				"league": league(Some(league_)),
+			}));
+		}
+	}
+	Ok(Some(json!(entries)))
+}
+
+// Players tracked in a Telegram chat (by identifier) or a sink (by name)
+fn chat_trackers(state: &State, chat: &str) -> Option<serde_json::Value> {
+	let chat = match chat.parse() {
+		Ok(telegram_chat) => notifier::Chat::Telegram(api::telegram::ChatId(telegram_chat)),
+		Err(_) => notifier::Chat::Sink(String::from(chat)),
+	};
+	let names_platforms = |names_platforms_chats: &Tracked| {
+		names_platforms_chats
+			.iter()
+			.filter(|(_, chats)| chats.contains(&chat))
+			.map(
+				|((name, platform), _)| json!({ "name": name, "platform": platform.as_region_str() }),
+			)
+			.collect::<Vec<_>>()
+	};
+	let lol = names_platforms(&state.lol_names_platforms_chats);
+	let tft = names_platforms(&state.tft_names_platforms_chats);
+	(!lol.is_empty() || !tft.is_empty()).then(|| json!({ "lol": lol, "tft": tft }))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	async fn get(state: &State, path: &str, token: &str) -> (StatusCode, serde_json::Value) {
+		let request = Request::get(path)
+			.header(hyper::header::AUTHORIZATION, format!("Bearer {token}"))
+			.body(Body::empty())
+			.unwrap();
+		let response = handle(state, request).await;
+		let status = response.status();
+		let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+		(status, serde_json::from_slice(&body).unwrap())
+	}
+
+	#[tokio::test]
+	async fn routes() {
+		let db_pool = sqlx::any::AnyPoolOptions::new()
+			.max_connections(1)
+			.connect("sqlite::memory:")
+			.await
+			.unwrap();
+		db::migrate(&db_pool).await.unwrap();
+		let state = State {
+			db_pool,
+			token: String::from("TOKEN"),
+			lol_names_platforms_chats: Tracked::from([(
+				(String::from("A"), api::riot::Platform::EUW1),
+				BTreeSet::from([notifier::Chat::Telegram(api::telegram::ChatId(42))]),
+			)]),
+			tft_names_platforms_chats: Tracked::default(),
+		};
+
+		assert_eq!(
+			get(&state, "/players", "WRONG").await.0,
+			StatusCode::UNAUTHORIZED
+		);
+		assert_eq!(
+			get(&state, "/players", "TOKEN").await,
+			(StatusCode::OK, json!([]))
+		);
+		assert_eq!(
+			get(&state, "/players/puuid-a/games?since=0", "TOKEN").await,
+			(StatusCode::OK, json!([]))
+		);
+		assert_eq!(
+			get(&state, "/games/EUW1_1", "TOKEN").await.0,
+			StatusCode::NOT_FOUND
+		);
+		assert_eq!(
+			get(&state, "/leaderboard?product=lol", "TOKEN").await,
+			(StatusCode::OK, json!([]))
+		);
+		assert_eq!(
+			get(&state, "/chats/42/trackers", "TOKEN").await,
+			(
+				StatusCode::OK,
+				json!({ "lol": [{ "name": "A", "platform": "EUW" }], "tft": [] })
+			)
+		);
+		assert_eq!(
+			get(&state, "/chats/team-discord/trackers", "TOKEN").await.0,
+			StatusCode::NOT_FOUND
+		);
+	}
+
+	#[tokio::test]
+	async fn stored_game() {
+		let db_pool = sqlx::any::AnyPoolOptions::new()
+			.max_connections(1)
+			.connect("sqlite::memory:")
+			.await
+			.unwrap();
+		db::migrate(&db_pool).await.unwrap();
+		let game: api::riot::lol::Game =
+			serde_json::from_str(include_str!("../assets/tests/lol-game.json")).unwrap();
+		let player = |puuid: &str, name: &str| -> api::riot::lol::Player {
+			serde_json::from_value(json!({
+				"accountId": "", "profileIconId": 0, "revisionDate": 0, "name": name,
+				"id": name, "puuid": puuid, "summonerLevel": 100,
+			}))
+			.unwrap()
+		};
+		// Borrows the queue type, so parsed from a string
+		let league: api::riot::lol::League = serde_json::from_str(
+			&json!({
+				"summonerId": "A", "summonerName": "A", "queueType": "RANKED_SOLO_5x5",
+				"tier": "GOLD", "rank": "II", "leaguePoints": 42, "wins": 10, "losses": 8,
+				"hotStreak": false, "veteran": false, "freshBlood": false, "inactive": false,
+			})
+			.to_string(),
+		)
+		.unwrap();
+		let (a, b) = (player("puuid-a", "A"), player("puuid-b", "B"));
+		let platform = api::riot::Platform::EUW1;
+		db::riot::lol::insert_players(
+			&db_pool,
+			&[((a.clone(), platform), ()), ((b.clone(), platform), ())],
+		)
+		.await
+		.unwrap();
+		db::riot::lol::insert_game(
+			&db_pool,
+			&game,
+			platform,
+			&[
+				(&a, &game.info.participants[0], Some(league), ()),
+				(&b, &game.info.participants[1], None, ()),
+			],
+			false,
+		)
+		.await
+		.unwrap();
+		let state = State {
+			db_pool,
+			token: String::from("TOKEN"),
+			lol_names_platforms_chats: Tracked::default(),
+			tft_names_platforms_chats: Tracked::default(),
+		};
+		let gold = json!({ "tier": "GOLD", "rank": "II", "lp": 42 });
+
+		assert_eq!(
+			get(&state, "/players", "TOKEN").await,
+			(
+				StatusCode::OK,
+				json!([
+					{ "product": "lol", "puuid": "puuid-a", "name": "A" },
+					{ "product": "lol", "puuid": "puuid-b", "name": "B" },
+				])
+			)
+		);
+		assert_eq!(
+			get(
+				&state,
+				"/players/puuid-a/games?since=1700000000000",
+				"TOKEN"
+			)
+			.await,
+			(
+				StatusCode::OK,
+				json!([{
+					"product": "lol",
+					"match_id": "EUW1_1",
+					"queue": 420,
+					"timestamp": 1_700_000_005_000_i64,
+					"result": 1,
+					"league": gold,
+					"champion": "Ahri",
+					"kills_deaths_assists": [10, 2, 8],
+				}])
+			)
+		);
+		assert_eq!(
+			get(
+				&state,
+				"/players/puuid-a/games?since=1700000006000",
+				"TOKEN"
+			)
+			.await,
+			(StatusCode::OK, json!([]))
+		);
+		assert_eq!(
+			get(&state, "/games/EUW1_1", "TOKEN").await,
+			(
+				StatusCode::OK,
+				json!({
+					"product": "lol",
+					"match_id": "EUW1_1",
+					"platform": "EUW1",
+					"queue": 420,
+					"timestamp": 1_700_000_005_000_i64,
+					"participants": [
+						{ "puuid": "puuid-a", "name": "A", "result": 1, "league": gold },
+						{ "puuid": "puuid-b", "name": "B", "result": 0, "league": null },
+					],
+				})
+			)
+		);
+		assert_eq!(
+			get(&state, "/leaderboard?queue=420", "TOKEN").await,
+			(
+				StatusCode::OK,
+				json!([{
+					"product": "lol",
+					"queue": 420,
+					"puuid": "puuid-a",
+					"name": "A",
+					"league": gold,
+				}])
+			)
+		);
+		assert_eq!(
+			get(&state, "/leaderboard?queue=440", "TOKEN").await,
+			(StatusCode::OK, json!([]))
+		);
+	}
+
+	#[test]
+	fn tokens() {
+		assert!(same_token("TOKEN", "TOKEN"));
+		assert!(!same_token("TOKEM", "TOKEN"));
+		assert!(!same_token("TOKE", "TOKEN"));
+		assert!(!same_token("", "TOKEN"));
+	}
+}
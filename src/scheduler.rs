@@ -0,0 +1,187 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use tokio::time::{Duration, Instant};
+
+use super::config::riot::Polling;
+
+pub enum Activity {
+	// Not in a game, polled again after a time that grows with the time since
+	// their last game
+	Idle,
+	// Currently in a game
+	Playing,
+}
+
+pub struct Scheduler {
+	polling: Polling,
+	last_games: Vec<Option<i64>>,
+	queue: BinaryHeap<Reverse<(Instant, usize)>>,
+	// When every player is due, none while being polled. Entries of the queue
+	// that no longer match were replaced by an earlier poll.
+	due: Vec<Option<Instant>>,
+}
+
+impl Scheduler {
+	pub fn new(polling: &Polling, last_games: Vec<Option<i64>>) -> Self {
+		// Poll everyone once at startup, most recently active players first
+		let now = Instant::now();
+		let mut indexes = Vec::from_iter(0..last_games.len());
+		indexes.sort_by_key(|index| Reverse(last_games[*index]));
+		let mut due = vec![None; last_games.len()];
+		let queue = indexes
+			.into_iter()
+			.enumerate()
+			.map(|(order, index)| {
+				let at = now + Duration::from_millis(order as u64);
+				due[index] = Some(at);
+				Reverse((at, index))
+			})
+			.collect();
+
+		Self {
+			polling: polling.clone(),
+			last_games,
+			queue,
+			due,
+		}
+	}
+
+	// Wait until the next player is due, requests then wait for the rate budget.
+	// Cancel safe, so that it can wait alongside `played`.
+	pub async fn next(&mut self) -> usize {
+		loop {
+			let Some(Reverse((at, index))) = self.queue.peek().copied() else {
+				return std::future::pending().await;
+			};
+			tokio::time::sleep_until(at).await;
+			self.queue.pop();
+			if self.due[index] == Some(at) {
+				self.due[index] = None;
+				return index;
+			}
+		}
+	}
+
+	pub fn reschedule(&mut self, index: usize, activity: Activity) {
+		let interval = match activity {
+			Activity::Playing => self.polling.min_interval as f64,
+			Activity::Idle => self.last_games[index].map_or(
+				self.polling.max_interval as f64,
+				self.idle_interval(chrono::Utc::now().timestamp_millis()),
+			),
+		};
+
+		tracing::trace!(index, interval, "Rescheduling player poll");
+
+		self.schedule(index, Instant::now() + Duration::from_secs_f64(interval));
+	}
+
+	// A new game of a player has been stored, which brings their next poll
+	// forward if it ended after the last one known
+	pub fn played(&mut self, index: usize, game_end: i64) {
+		if self.last_games[index].is_some_and(|last_game| last_game >= game_end) {
+			return;
+		}
+		self.last_games[index] = Some(game_end);
+		let interval = self.idle_interval(chrono::Utc::now().timestamp_millis())(game_end);
+		let at = Instant::now() + Duration::from_secs_f64(interval);
+		if self.due[index].is_some_and(|due| at < due) {
+			self.schedule(index, at);
+		}
+	}
+
+	fn schedule(&mut self, index: usize, at: Instant) {
+		self.due[index] = Some(at);
+		self.queue.push(Reverse((at, index)));
+	}
+
+	fn idle_interval(&self, now: i64) -> impl Fn(i64) -> f64 {
+		let Polling {
+			min_interval,
+			max_interval,
+			idle_factor,
+			..
+		} = self.polling;
+		move |last_game| {
+			let idle = (now - last_game).max(0) as f64 / 1000.0;
+			(idle * idle_factor).clamp(min_interval as f64, max_interval.max(min_interval) as f64)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn polling() -> Polling {
+		Polling {
+			min_interval: 20,
+			max_interval: 3600,
+			idle_factor: 0.01,
+			..Polling::default()
+		}
+	}
+
+	#[tokio::test(start_paused = true)]
+	async fn order() {
+		let now = chrono::Utc::now().timestamp_millis();
+		let mut scheduler =
+			Scheduler::new(&polling(), vec![Some(now - 3_600_000), None, Some(now)]);
+
+		// Most recently active players first at startup
+		assert_eq!(scheduler.next().await, 2);
+		assert_eq!(scheduler.next().await, 0);
+		assert_eq!(scheduler.next().await, 1);
+
+		let start = Instant::now();
+		// Never played, idle for an hour, playing
+		scheduler.reschedule(1, Activity::Idle);
+		scheduler.reschedule(0, Activity::Idle);
+		scheduler.reschedule(2, Activity::Playing);
+		assert_eq!(scheduler.next().await, 2);
+		assert_eq!(start.elapsed().as_secs(), 20);
+		assert_eq!(scheduler.next().await, 0);
+		assert_eq!(start.elapsed().as_secs(), 36);
+
+		// A new game brings the player back to the shortest interval, an older
+		// one does not
+		scheduler.reschedule(0, Activity::Idle);
+		scheduler.played(0, chrono::Utc::now().timestamp_millis());
+		assert_eq!(scheduler.next().await, 0);
+		assert_eq!(start.elapsed().as_secs(), 56);
+		scheduler.reschedule(0, Activity::Idle);
+		scheduler.played(0, now - 3_600_000);
+		// Waiting can be cancelled without losing the next player
+		assert!(
+			tokio::time::timeout(Duration::from_secs(10), scheduler.next())
+				.await
+				.is_err()
+		);
+		assert_eq!(scheduler.next().await, 0);
+		assert_eq!(start.elapsed().as_secs(), 76);
+		assert_eq!(scheduler.next().await, 1);
+		assert_eq!(start.elapsed().as_secs(), 3600);
+	}
+
+	#[test]
+	fn idle_backoff() {
+		let now = 1_700_000_000_000;
+		let scheduler = Scheduler::new(&polling(), Vec::default());
+		let idle_interval = scheduler.idle_interval(now);
+		assert_eq!(idle_interval(now), 20.0);
+		assert_eq!(idle_interval(now + 60_000), 20.0);
+		assert_eq!(idle_interval(now - 3_600_000), 36.0);
+		assert_eq!(idle_interval(now - 100 * 3_600_000), 3600.0);
+
+		// Intervals stay between min-interval and max-interval, even if inverted
+		let scheduler = Scheduler::new(
+			&Polling {
+				min_interval: 60,
+				max_interval: 30,
+				..polling()
+			},
+			Vec::default(),
+		);
+		assert_eq!(scheduler.idle_interval(now)(now - 100 * 3_600_000), 60.0);
+	}
+}
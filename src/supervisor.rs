@@ -0,0 +1,259 @@
+use std::{any::Any, future::Future};
+
+use tokio::{sync::watch, task::JoinHandle, time::Duration};
+
+use super::metrics;
+
+// Restarts wait from 1 second, doubling up to 5 minutes. Tasks that ran for
+// longer than that start over from 1 second.
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+pub struct Supervisor {
+	shutdown: watch::Sender<bool>,
+}
+
+impl Default for Supervisor {
+	fn default() -> Self {
+		Self {
+			shutdown: watch::channel(false).0,
+		}
+	}
+}
+
+impl Supervisor {
+	// Run a task until shutdown, restarting it whenever it ends or panics
+	pub async fn supervise<F, T>(&self, name: &'static str, task: F)
+	where
+		F: Fn() -> T,
+		T: Future<Output = ()> + Send + 'static,
+	{
+		self.run(name, task, true).await
+	}
+
+	// Run a task that ends by itself after shutdown (see `until_stopped`), e.g.
+	// to finish its pending work, restarting it whenever it ends or panics before
+	pub async fn supervise_draining<F, T>(&self, name: &'static str, task: F)
+	where
+		F: Fn() -> T,
+		T: Future<Output = ()> + Send + 'static,
+	{
+		self.run(name, task, false).await
+	}
+
+	// Every run is a task of its own, aborted on shutdown when cancelled or when
+	// no longer supervised
+	async fn run<F, T>(&self, name: &'static str, task: F, cancel: bool)
+	where
+		F: Fn() -> T,
+		T: Future<Output = ()> + Send + 'static,
+	{
+		let mut shutdown = self.shutdown.subscribe();
+		let mut backoff = MIN_BACKOFF;
+		loop {
+			let started = tokio::time::Instant::now();
+			let exit = {
+				let _up = metrics::Task::up(name);
+				let mut handle = Aborting(tokio::spawn(task()));
+				tokio::select! {
+					exit = &mut handle.0 => exit,
+					_ = stopped(&mut shutdown), if cancel => return,
+				}
+			};
+			if *shutdown.borrow() && exit.is_ok() {
+				return;
+			}
+			match exit {
+				Ok(()) => tracing::error!(task = name, "Task has ended unexpectedly"),
+				Err(err) if err.is_panic() => tracing::error!(
+					task = name,
+					panic = panic_message(err.into_panic().as_ref()),
+					"Task has panicked"
+				),
+				Err(err) => tracing::error!(
+					task = name,
+					error = err.to_string(),
+					"Task has been cancelled"
+				),
+			}
+
+			if started.elapsed() > MAX_BACKOFF {
+				backoff = MIN_BACKOFF;
+			}
+			metrics::task_restarted(name);
+			tracing::warn!(task = name, backoff = backoff.as_secs(), "Restarting task");
+			tokio::select! {
+				_ = tokio::time::sleep(backoff) => {},
+				_ = stopped(&mut shutdown) => return,
+			}
+			backoff = (backoff * 2).min(MAX_BACKOFF);
+		}
+	}
+
+	// Stop every supervised task
+	pub fn shutdown(&self) {
+		self.shutdown.send_replace(true);
+	}
+
+	pub async fn stopped(&self) {
+		stopped(&mut self.shutdown.subscribe()).await
+	}
+
+	pub fn is_stopped(&self) -> bool {
+		*self.shutdown.borrow()
+	}
+
+	// Output of `future` if ready, or nothing once shut down while it is pending
+	pub async fn until_stopped<T>(&self, future: impl Future<Output = T>) -> Option<T> {
+		tokio::select! {
+			biased;
+			output = future => Some(output),
+			_ = self.stopped() => None,
+		}
+	}
+}
+
+// CTRL-C, or SIGTERM as sent by container runtimes
+pub async fn shutdown_signal() {
+	#[cfg(unix)]
+	let terminate = async {
+		match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+			Ok(mut terminate) => {
+				terminate.recv().await;
+			}
+			Err(err) => {
+				tracing::error!(error = err.to_string(), "Error handling SIGTERM signal");
+				std::future::pending().await
+			}
+		}
+	};
+	#[cfg(not(unix))]
+	let terminate = std::future::pending::<()>();
+
+	tokio::select! {
+		signal = tokio::signal::ctrl_c() => {
+			signal.unwrap_or_else(|err| {
+				tracing::error!(error = err.to_string(), "Error handling CTRL-C signal")
+			})
+		},
+		_ = terminate => {},
+	}
+	tracing::info!("Shutting down");
+}
+
+// Task aborted when dropped, instead of being detached
+struct Aborting(JoinHandle<()>);
+
+impl Drop for Aborting {
+	fn drop(&mut self) {
+		self.0.abort();
+	}
+}
+
+async fn stopped(shutdown: &mut watch::Receiver<bool>) {
+	while !*shutdown.borrow() {
+		if shutdown.changed().await.is_err() {
+			return;
+		}
+	}
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+	panic
+		.downcast_ref::<&str>()
+		.copied()
+		.or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+		.unwrap_or("Unknown panic")
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::atomic::{AtomicU32, Ordering};
+
+	use super::*;
+
+	#[tokio::test]
+	async fn restart_and_shutdown() {
+		let _tasks = metrics::tests::lock_tasks().await;
+		let supervisor: &'static Supervisor = Box::leak(Box::default());
+		let runs: &'static AtomicU32 = Box::leak(Box::default());
+		supervisor
+			.supervise("supervisor_restart", || async {
+				if runs.fetch_add(1, Ordering::Relaxed) == 0 {
+					panic!("First run");
+				}
+				supervisor.shutdown();
+				std::future::pending().await
+			})
+			.await;
+		assert_eq!(runs.load(Ordering::Relaxed), 2);
+		metrics::tests::forget_task("supervisor_restart");
+	}
+
+	#[tokio::test]
+	async fn drain() {
+		let _tasks = metrics::tests::lock_tasks().await;
+		let supervisor: &'static Supervisor = Box::leak(Box::default());
+		let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+		let receiver: &'static _ = Box::leak(Box::new(tokio::sync::Mutex::new(receiver)));
+		let received: &'static _ = Box::leak(Box::new(std::sync::Mutex::new(Vec::default())));
+		sender.send(1).unwrap();
+		sender.send(2).unwrap();
+		supervisor.shutdown();
+		supervisor
+			.supervise_draining("supervisor_drain", || async {
+				let mut receiver = receiver.lock().await;
+				while let Some(Some(item)) = supervisor.until_stopped(receiver.recv()).await {
+					received.lock().unwrap().push(item);
+				}
+			})
+			.await;
+		assert_eq!(*received.lock().unwrap(), [1, 2]);
+		metrics::tests::forget_task("supervisor_drain");
+	}
+
+	// A task blocking its thread does not hold back the others
+	#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+	async fn isolation() {
+		let _tasks = metrics::tests::lock_tasks().await;
+		let supervisor: &'static Supervisor = Box::leak(Box::default());
+		let (sender, receiver) = std::sync::mpsc::channel::<()>();
+		let receiver: &'static _ = Box::leak(Box::new(std::sync::Mutex::new(receiver)));
+		tokio::join!(
+			supervisor.supervise("supervisor_blocking", || async {
+				receiver.lock().unwrap().recv().unwrap_or_default();
+				std::future::pending().await
+			}),
+			supervisor.supervise("supervisor_isolated", move || {
+				let sender = sender.clone();
+				async move {
+					tokio::time::sleep(Duration::from_millis(10)).await;
+					supervisor.shutdown();
+					sender.send(()).unwrap_or_default();
+				}
+			}),
+		);
+		metrics::tests::forget_task("supervisor_blocking");
+		metrics::tests::forget_task("supervisor_isolated");
+	}
+
+	// Tasks no longer supervised are aborted
+	#[tokio::test]
+	async fn abort() {
+		let _tasks = metrics::tests::lock_tasks().await;
+		let supervisor = Supervisor::default();
+		let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<()>();
+		let supervised = supervisor.supervise("supervisor_abort", move || {
+			let sender = sender.clone();
+			async move {
+				let _sender = sender;
+				std::future::pending().await
+			}
+		});
+		assert!(tokio::time::timeout(Duration::from_millis(10), supervised)
+			.await
+			.is_err());
+		assert_eq!(receiver.recv().await, None);
+		metrics::tests::forget_task("supervisor_abort");
+	}
+}